@@ -0,0 +1,223 @@
+package com.example.android_rust_experiments
+
+import android.app.Activity
+import android.content.Context
+import android.graphics.Rect
+import android.os.Bundle
+import android.view.View
+import android.view.accessibility.AccessibilityEvent
+import android.view.accessibility.AccessibilityManager
+import android.view.accessibility.AccessibilityNodeInfo
+import android.view.accessibility.AccessibilityNodeProvider
+
+/**
+ * Shows the AccessKit tree of egui to TalkBack as virtual views of the activity, the views come
+ * from AndroidAccessibility in src/accessibility.rs
+ */
+object AccessibilityBridge {
+    // Same flags as AndroidAccessibility
+    private const val CLICKABLE = 1
+    private const val FOCUSABLE = 2
+    private const val FOCUSED = 4
+    private const val CHECKABLE = 8
+    private const val CHECKED = 16
+
+    // Same actions as AndroidAccessibility::take_action_requests
+    private const val ACTION_CLICK = 0
+    private const val ACTION_FOCUS = 1
+
+    private const val HOST_ID = AccessibilityNodeProvider.HOST_VIEW_ID
+    private const val NO_ID = Int.MIN_VALUE
+
+    private class VirtualView(
+        val parent: Int,
+        val bounds: Rect,
+        val label: String,
+        val value: String?,
+        val className: String,
+        val flags: Int,
+    ) {
+        val children = ArrayList<Int>()
+
+        fun has(flag: Int) = flags and flag != 0
+    }
+
+    /** Guards everything below, the Rust side calls in from its own thread */
+    private val lock = Any()
+    private var host: View? = null
+    private var views = LinkedHashMap<Int, VirtualView>()
+    private var topLevel = ArrayList<Int>()
+    private var accessibilityFocus = NO_ID
+    private val actions = ArrayList<Int>()
+
+    /** Wakes up the event loop of the Rust side, which then takes the actions */
+    @JvmStatic
+    private external fun requestFrame()
+
+    @JvmStatic
+    fun isEnabled(context: Context): Boolean =
+        context.getSystemService(AccessibilityManager::class.java).isEnabled
+
+    @JvmStatic
+    fun attach(activity: Activity) {
+        activity.runOnUiThread {
+            val decorView = activity.window.decorView
+            synchronized(lock) { host = decorView }
+
+            decorView.accessibilityDelegate = object : View.AccessibilityDelegate() {
+                override fun getAccessibilityNodeProvider(host: View) = provider
+            }
+        }
+    }
+
+    /** The views in the order of the tree, parents before their children */
+    @JvmStatic
+    fun update(
+        ids: IntArray,
+        parents: IntArray,
+        bounds: IntArray,
+        labels: Array<String>,
+        values: Array<String?>,
+        classNames: Array<String>,
+        flags: IntArray,
+    ) {
+        val host = synchronized(lock) {
+            views = LinkedHashMap()
+            topLevel = ArrayList()
+
+            for (index in ids.indices) {
+                val bound = index * 4
+                views[ids[index]] = VirtualView(
+                    parents[index],
+                    Rect(bounds[bound], bounds[bound + 1], bounds[bound + 2], bounds[bound + 3]),
+                    labels[index],
+                    values[index],
+                    classNames[index],
+                    flags[index],
+                )
+
+                val parent = views[parents[index]]
+                if (parent != null) parent.children.add(ids[index]) else topLevel.add(ids[index])
+            }
+
+            if (accessibilityFocus !in views) accessibilityFocus = NO_ID
+
+            host
+        }
+
+        host?.post { sendEvent(HOST_ID, AccessibilityEvent.TYPE_WINDOW_CONTENT_CHANGED) }
+    }
+
+    /** Pairs of a view id and its action since the last call */
+    @JvmStatic
+    fun takeActions(): IntArray = synchronized(lock) {
+        val taken = actions.toIntArray()
+        actions.clear()
+        taken
+    }
+
+    private val provider = object : AccessibilityNodeProvider() {
+        override fun createAccessibilityNodeInfo(virtualViewId: Int): AccessibilityNodeInfo? =
+            synchronized(lock) {
+                val host = host ?: return null
+
+                if (virtualViewId == HOST_ID) {
+                    val info = AccessibilityNodeInfo.obtain(host)
+                    host.onInitializeAccessibilityNodeInfo(info)
+                    topLevel.forEach { info.addChild(host, it) }
+                    return info
+                }
+
+                val view = views[virtualViewId] ?: return null
+                val info = AccessibilityNodeInfo.obtain(host, virtualViewId)
+                val location = IntArray(2)
+                host.getLocationOnScreen(location)
+                val bounds = Rect(view.bounds)
+                bounds.offset(location[0], location[1])
+
+                info.setParent(host, view.parent)
+                view.children.forEach { info.addChild(host, it) }
+                info.setBoundsInScreen(bounds)
+                info.packageName = host.context.packageName
+                info.className = view.className
+                info.text = listOfNotNull(view.label.ifEmpty { null }, view.value).joinToString(", ")
+                info.isEnabled = true
+                info.isVisibleToUser = true
+
+                if (view.has(CLICKABLE)) {
+                    info.isClickable = true
+                    info.addAction(AccessibilityNodeInfo.AccessibilityAction.ACTION_CLICK)
+                }
+                if (view.has(FOCUSABLE)) {
+                    info.isFocusable = true
+                    info.isFocused = view.has(FOCUSED)
+                    info.addAction(AccessibilityNodeInfo.AccessibilityAction.ACTION_FOCUS)
+                }
+                info.isCheckable = view.has(CHECKABLE)
+                info.isChecked = view.has(CHECKED)
+
+                info.isAccessibilityFocused = accessibilityFocus == virtualViewId
+                info.addAction(
+                    if (info.isAccessibilityFocused) {
+                        AccessibilityNodeInfo.AccessibilityAction.ACTION_CLEAR_ACCESSIBILITY_FOCUS
+                    } else {
+                        AccessibilityNodeInfo.AccessibilityAction.ACTION_ACCESSIBILITY_FOCUS
+                    }
+                )
+
+                info
+            }
+
+        override fun performAction(virtualViewId: Int, action: Int, arguments: Bundle?): Boolean {
+            val host = synchronized(lock) { host } ?: return false
+
+            if (virtualViewId == HOST_ID) {
+                return host.performAccessibilityAction(action, arguments)
+            }
+
+            when (action) {
+                AccessibilityNodeInfo.ACTION_ACCESSIBILITY_FOCUS -> {
+                    val previous = synchronized(lock) {
+                        val previous = accessibilityFocus
+                        accessibilityFocus = virtualViewId
+                        previous
+                    }
+                    if (previous != NO_ID) {
+                        sendEvent(previous, AccessibilityEvent.TYPE_VIEW_ACCESSIBILITY_FOCUS_CLEARED)
+                    }
+                    sendEvent(virtualViewId, AccessibilityEvent.TYPE_VIEW_ACCESSIBILITY_FOCUSED)
+                }
+                AccessibilityNodeInfo.ACTION_CLEAR_ACCESSIBILITY_FOCUS -> {
+                    synchronized(lock) { accessibilityFocus = NO_ID }
+                    sendEvent(virtualViewId, AccessibilityEvent.TYPE_VIEW_ACCESSIBILITY_FOCUS_CLEARED)
+                }
+                AccessibilityNodeInfo.ACTION_CLICK -> {
+                    queue(virtualViewId, ACTION_CLICK)
+                    sendEvent(virtualViewId, AccessibilityEvent.TYPE_VIEW_CLICKED)
+                }
+                AccessibilityNodeInfo.ACTION_FOCUS -> queue(virtualViewId, ACTION_FOCUS)
+                else -> return false
+            }
+
+            return true
+        }
+    }
+
+    private fun queue(virtualViewId: Int, action: Int) {
+        synchronized(lock) {
+            actions.add(virtualViewId)
+            actions.add(action)
+        }
+        requestFrame()
+    }
+
+    private fun sendEvent(virtualViewId: Int, type: Int) {
+        val host = synchronized(lock) { host } ?: return
+        val parent = host.parent ?: return
+
+        val event = AccessibilityEvent.obtain(type)
+        event.packageName = host.context.packageName
+        event.setSource(host, virtualViewId)
+        parent.requestSendAccessibilityEvent(host, event)
+    }
+}
@@ -0,0 +1,76 @@
+package com.example.android_rust_experiments
+
+import android.Manifest
+import android.app.Notification
+import android.app.NotificationChannel
+import android.app.NotificationManager
+import android.content.Context
+import android.content.pm.PackageManager
+import android.os.Build
+import androidx.work.Data
+import androidx.work.ExistingWorkPolicy
+import androidx.work.OneTimeWorkRequest
+import androidx.work.WorkManager
+import androidx.work.Worker
+import androidx.work.WorkerParameters
+import java.util.concurrent.TimeUnit
+
+/** Posts a reminder the Rust side scheduled, also when the app isn't running anymore */
+class ReminderWorker(context: Context, params: WorkerParameters) : Worker(context, params) {
+    override fun doWork(): Result {
+        val context = applicationContext
+
+        if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.TIRAMISU &&
+            context.checkSelfPermission(Manifest.permission.POST_NOTIFICATIONS) !=
+            PackageManager.PERMISSION_GRANTED
+        ) {
+            return Result.success()
+        }
+
+        val manager = context.getSystemService(NotificationManager::class.java)
+        // Same channel as AndroidNotifications in src/notifications.rs
+        manager.createNotificationChannel(
+            NotificationChannel(CHANNEL_ID, "Reminders", NotificationManager.IMPORTANCE_DEFAULT)
+        )
+
+        val notification = Notification.Builder(context, CHANNEL_ID)
+            .setSmallIcon(android.R.drawable.ic_dialog_info)
+            .setContentTitle(inputData.getString(KEY_TITLE))
+            .setContentText(inputData.getString(KEY_BODY))
+            .build()
+        manager.notify(inputData.getInt(KEY_ID, 0), notification)
+
+        return Result.success()
+    }
+
+    companion object {
+        private const val CHANNEL_ID = "reminders"
+        private const val KEY_ID = "id"
+        private const val KEY_TITLE = "title"
+        private const val KEY_BODY = "body"
+
+        /** Replaces the reminder with the same id */
+        @JvmStatic
+        fun schedule(context: Context, id: Int, title: String, body: String, delayMillis: Long) {
+            val data = Data.Builder()
+                .putInt(KEY_ID, id)
+                .putString(KEY_TITLE, title)
+                .putString(KEY_BODY, body)
+                .build()
+            val request = OneTimeWorkRequest.Builder(ReminderWorker::class.java)
+                .setInitialDelay(delayMillis, TimeUnit.MILLISECONDS)
+                .setInputData(data)
+                .build()
+
+            WorkManager.getInstance(context)
+                .enqueueUniqueWork(workName(id), ExistingWorkPolicy.REPLACE, request)
+        }
+
+        @JvmStatic
+        fun cancel(context: Context, id: Int) {
+            WorkManager.getInstance(context).cancelUniqueWork(workName(id))
+        }
+
+        private fun workName(id: Int) = "reminder-$id"
+    }
+}
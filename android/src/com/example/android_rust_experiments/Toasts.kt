@@ -0,0 +1,12 @@
+package com.example.android_rust_experiments
+
+import android.app.Activity
+import android.widget.Toast
+
+/** Messages for when egui can't draw, see platform::show_error in src/platform.rs */
+object Toasts {
+    @JvmStatic
+    fun show(activity: Activity, text: String) {
+        activity.runOnUiThread { Toast.makeText(activity, text, Toast.LENGTH_LONG).show() }
+    }
+}
@@ -0,0 +1,82 @@
+use std::collections::BTreeMap;
+
+use android_rust_experiments::{AppState, Dates, Event, Message, MessageBus, Settings, StateView};
+use chrono::{Days, Local, NaiveDate};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+
+const NUMBER_OF_DATES: usize = 100_000;
+
+/// Roughly ten events per day going back from today
+fn synthetic_dates() -> Vec<NaiveDate> {
+    let today = Local::now().date_naive();
+
+    (0..NUMBER_OF_DATES)
+        .map(|index| today - Days::new(index as u64 / 10))
+        .collect()
+}
+
+fn state(dates: Vec<NaiveDate>) -> AppState {
+    AppState::new(
+        dates.into_iter().map(Event::new).collect(),
+        Vec::new(),
+        BTreeMap::new(),
+        Settings::default(),
+        Local::now().date_naive(),
+    )
+}
+
+fn dates_benchmark(c: &mut Criterion) {
+    let dates = synthetic_dates();
+
+    c.bench_function("load state 100k", |b| {
+        b.iter_batched(
+            || dates.clone(),
+            |dates| black_box(state(dates)),
+            BatchSize::LargeInput,
+        )
+    });
+
+    let mut state = state(dates.clone());
+
+    c.bench_function("add and remove date 100k", |b| {
+        b.iter(|| {
+            let date = NaiveDate::from_ymd_opt(2000, 6, 15).unwrap();
+            state.apply(&Message::EventAdded(Event::new(black_box(date))));
+            state.apply(&Message::EventRemoved(Event::new(date)));
+        })
+    });
+
+    let mut view = Dates::default();
+    let mut bus = MessageBus::default();
+
+    let ctx = egui::Context::default();
+
+    // `Dates::ui` relies on the text styles that `MainWindows` registers
+    let mut style = (*ctx.style()).clone();
+    style.text_styles.insert(
+        egui::TextStyle::Name("DateInputButton".into()),
+        egui::FontId::proportional(45.0),
+    );
+    ctx.set_style(style);
+
+    c.bench_function("ui frame 100k", |b| {
+        b.iter(|| {
+            let raw_input = egui::RawInput {
+                screen_rect: Some(egui::Rect::from_min_size(
+                    egui::Pos2::ZERO,
+                    egui::vec2(1080.0, 2340.0),
+                )),
+                ..Default::default()
+            };
+
+            let full_output = ctx.run(raw_input, |ctx| {
+                egui::CentralPanel::default().show(ctx, |ui| view.ui(ui, &state, &mut bus));
+            });
+
+            black_box(ctx.tessellate(full_output.shapes))
+        })
+    });
+}
+
+criterion_group!(benches, dates_benchmark);
+criterion_main!(benches);
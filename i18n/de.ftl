@@ -0,0 +1,187 @@
+## Dates
+
+date = { $day }. { $month } { $year }
+date-long = { $weekday }, { $day }. { $month } { $year }
+date-pattern = %d.%m.%Y
+month-of-year = { $month } { $year }
+
+month-1 = Januar
+month-2 = Februar
+month-3 = März
+month-4 = April
+month-5 = Mai
+month-6 = Juni
+month-7 = Juli
+month-8 = August
+month-9 = September
+month-10 = Oktober
+month-11 = November
+month-12 = Dezember
+
+month-short-1 = Jan.
+month-short-2 = Feb.
+month-short-3 = März
+month-short-4 = Apr.
+month-short-5 = Mai
+month-short-6 = Juni
+month-short-7 = Juli
+month-short-8 = Aug.
+month-short-9 = Sept.
+month-short-10 = Okt.
+month-short-11 = Nov.
+month-short-12 = Dez.
+
+weekday-mon = Montag
+weekday-tue = Dienstag
+weekday-wed = Mittwoch
+weekday-thu = Donnerstag
+weekday-fri = Freitag
+weekday-sat = Samstag
+weekday-sun = Sonntag
+
+weekday-short-mon = Mo
+weekday-short-tue = Di
+weekday-short-wed = Mi
+weekday-short-thu = Do
+weekday-short-fri = Fr
+weekday-short-sat = Sa
+weekday-short-sun = So
+
+## Navigation
+
+screen-home = Start
+screen-dates = Termine
+screen-settings = Optionen
+shortcuts = Tastenkürzel
+quit = Beenden
+dismiss = Schließen
+save-error = Speichern fehlgeschlagen: { $error }
+save-file-error = { $file } konnte nicht gespeichert werden: { $error }
+gpu-surface-error = Der Bildschirm kann nicht gezeichnet werden, verlasse die App und öffne sie erneut
+window-error = Das Fenster kann nicht geöffnet werden
+
+## Home
+
+log-today = Heute eintragen
+log-today-hint = Fügt { $tracker } ein Ereignis für heute hinzu
+logged = { $date } eingetragen
+undo = Rückgängig
+home-consecutive-months = Monate in Folge: { $count }
+home-recent-events =
+    { $months ->
+        [12] Ereignisse im letzten Jahr: { $count }
+       *[other] Ereignisse der letzten { $months } Monate: { $count }
+    }
+home-week-events = Ereignisse diese Woche: { $count }
+
+## Goals
+
+goals = Ziele
+goal = { $target } pro { $period }
+goal-projected = Hochgerechnet: { $count }
+goal-result = { $start }: { $count } / { $target }
+delete-goal = Ziel { $goal } löschen
+goal-target = Anzahl
+goal-period = Zeitraum
+add-goal = Ziel hinzufügen
+period-week = Woche
+period-month = Monat
+period-year = Jahr
+period-rolling_year = 12 Monate
+
+## Dates screen
+
+consecutive-months = Monate in Folge: { $count }
+recent-events =
+    { $months ->
+        [12] Ereignisse im letzten Jahr: { $count }
+       *[other] Ereignisse der letzten { $months } Monate: { $count }
+    }
+delete-event = Ereignis vom { $date } löschen
+add-event = Ereignis am { $date } hinzufügen
+new-event-date = Datum des neuen Ereignisses
+new-event-date-hint = today, last friday, 05.03.2024
+unknown-date = Unbekanntes Datum
+select-event = Wähle ein Ereignis aus, um es hier zu sehen
+events-on-day =
+    { $count ->
+        [one] 1 Ereignis an diesem Tag
+       *[other] { $count } Ereignisse an diesem Tag
+    }
+today = Heute
+yesterday = Gestern
+in-days = In { $days } Tagen
+days-ago = Vor { $days } Tagen
+delete = Löschen
+cancel = Abbrechen
+delete-event-title = Ereignis löschen?
+
+## Date picker
+
+year = Jahr
+month = Monat
+day = Tag
+calendar = Kalender
+previous-month = Voriger Monat
+next-month = Nächster Monat
+
+## Reminders
+
+reminders = Erinnerungen: { $tracker }
+reminder-streak-at-risk = Bevor eine Serie abreißt
+reminder-recurring = Tage seit dem letzten Ereignis
+days-suffix = { " " }Tage
+notification-streak-at-risk = Serie in Gefahr: { $tracker }
+notification-streak-at-risk-body = Trage diesen Monat ein Ereignis ein, um deine Serie von { $count } Monaten zu halten
+notification-recurring = Erinnerung: { $tracker }
+notification-recurring-body = Zeit, das nächste Ereignis einzutragen
+
+## Settings
+
+theme = Design
+theme-system = System
+theme-light = Hell
+theme-dark = Dunkel
+accent-color = Akzentfarbe
+text-scale = Textgröße
+language = Sprache
+language-system = System ({ $language })
+week-start = Woche beginnt am
+date-format = Datumsformat
+date-format-automatic = Automatisch ({ $example })
+picker-years = Jahre in der Datumsauswahl
+statistic-months = Monate in der Statistik
+confirm-delete = Vor dem Löschen nachfragen
+layout = Layout
+layout-automatic = Automatisch
+layout-mobile = Mobil
+layout-desktop = Desktop
+default-tracker = Standard-Tracker
+new-tracker = Neuer Tracker
+add-tracker = Hinzufügen
+graphics = Grafik
+graphics-restart = Wird nach einem Neustart übernommen, nicht unterstützte Einstellungen fallen auf unterstützte zurück
+anti-aliasing = Kantenglättung
+present-mode = Darstellungsmodus
+present-mode-vsync = VSync
+present-mode-mailbox = Mailbox
+present-mode-immediate = Sofort
+gpu = GPU
+power-preference-low = Stromsparend
+power-preference-high = Hohe Leistung
+backend = Backend
+backend-auto = Automatisch
+
+## Keyboard shortcuts
+
+keyboard-shortcuts = Tastenkürzel
+shortcut-new-entry = Datum eines neuen Ereignisses eingeben
+shortcut-add = Eingegebenes Datum hinzufügen
+shortcut-select = Voriges oder nächstes Ereignis auswählen
+shortcut-delete = Ausgewähltes Ereignis löschen
+shortcut-undo = Rückgängig
+shortcut-redo = Wiederholen
+shortcut-cheat-sheet = Tastenkürzel zeigen oder verbergen
+shortcut-profiler = Profiler zeigen oder verbergen
+key-enter = Eingabe
+key-delete = Entf
@@ -0,0 +1,190 @@
+# Messages are looked up by id in the catalog of the language, missing ones are taken from here
+
+## Dates
+
+date = { $month } { $day }, { $year }
+date-long = { $weekday }, { $month } { $day }, { $year }
+# For typed dates, in the notation of chrono
+date-pattern = %m/%d/%Y
+month-of-year = { $month } { $year }
+
+month-1 = January
+month-2 = February
+month-3 = March
+month-4 = April
+month-5 = May
+month-6 = June
+month-7 = July
+month-8 = August
+month-9 = September
+month-10 = October
+month-11 = November
+month-12 = December
+
+month-short-1 = Jan
+month-short-2 = Feb
+month-short-3 = Mar
+month-short-4 = Apr
+month-short-5 = May
+month-short-6 = Jun
+month-short-7 = Jul
+month-short-8 = Aug
+month-short-9 = Sep
+month-short-10 = Oct
+month-short-11 = Nov
+month-short-12 = Dec
+
+weekday-mon = Monday
+weekday-tue = Tuesday
+weekday-wed = Wednesday
+weekday-thu = Thursday
+weekday-fri = Friday
+weekday-sat = Saturday
+weekday-sun = Sunday
+
+weekday-short-mon = Mo
+weekday-short-tue = Tu
+weekday-short-wed = We
+weekday-short-thu = Th
+weekday-short-fri = Fr
+weekday-short-sat = Sa
+weekday-short-sun = Su
+
+## Navigation
+
+screen-home = Home
+screen-dates = Dates
+screen-settings = Settings
+shortcuts = Shortcuts
+quit = Quit
+dismiss = Dismiss
+save-error = Could not save: { $error }
+save-file-error = Could not save { $file }: { $error }
+gpu-surface-error = Cannot draw on the screen, leave the app and open it again to retry
+window-error = Cannot open a window
+
+## Home
+
+log-today = Log today
+log-today-hint = Adds an event for today to { $tracker }
+logged = Logged { $date }
+undo = Undo
+home-consecutive-months = Consecutive Months: { $count }
+home-recent-events =
+    { $months ->
+        [12] Events Last Year: { $count }
+       *[other] Events Last { $months } Months: { $count }
+    }
+home-week-events = Events This Week: { $count }
+
+## Goals
+
+goals = Goals
+goal = { $target } per { $period }
+goal-projected = Projected: { $count }
+goal-result = { $start }: { $count } / { $target }
+delete-goal = Delete goal { $goal }
+goal-target = Target
+goal-period = Period
+add-goal = Add goal
+period-week = week
+period-month = month
+period-year = year
+period-rolling_year = rolling year
+
+## Dates screen
+
+consecutive-months = Consecutive months: { $count }
+recent-events =
+    { $months ->
+        [12] Events last year: { $count }
+       *[other] Events in the last { $months } months: { $count }
+    }
+delete-event = Delete event { $date }
+add-event = Add event { $date }
+new-event-date = New event date
+new-event-date-hint = today, last friday, 2024-03-05
+unknown-date = Unknown date
+select-event = Select an event to see it here
+events-on-day =
+    { $count ->
+        [one] 1 event on this day
+       *[other] { $count } events on this day
+    }
+today = Today
+yesterday = Yesterday
+in-days = In { $days } days
+days-ago = { $days } days ago
+delete = Delete
+cancel = Cancel
+delete-event-title = Delete event?
+
+## Date picker
+
+year = Year
+month = Month
+day = Day
+calendar = Calendar
+previous-month = Previous month
+next-month = Next month
+
+## Reminders
+
+reminders = Reminders: { $tracker }
+reminder-streak-at-risk = Before a streak lapses
+reminder-recurring = Days since the last event
+days-suffix = { " " }days
+notification-streak-at-risk = Streak at risk: { $tracker }
+notification-streak-at-risk-body = Log an event this month to keep your streak of { $count } months
+notification-recurring = Reminder: { $tracker }
+notification-recurring-body = Time to log the next event
+
+## Settings
+
+theme = Theme
+theme-system = System
+theme-light = Light
+theme-dark = Dark
+accent-color = Accent color
+text-scale = Text scale
+language = Language
+language-system = System ({ $language })
+week-start = Week starts on
+date-format = Date format
+date-format-automatic = Automatic ({ $example })
+picker-years = Years in date picker
+statistic-months = Months in statistics
+confirm-delete = Confirm before deleting
+layout = Layout
+layout-automatic = Automatic
+layout-mobile = Mobile
+layout-desktop = Desktop
+default-tracker = Default tracker
+new-tracker = New tracker
+add-tracker = Add
+graphics = Graphics
+graphics-restart = Applied after a restart, unsupported settings fall back to supported ones
+anti-aliasing = Anti-aliasing
+present-mode = Present mode
+present-mode-vsync = Vsync
+present-mode-mailbox = Mailbox
+present-mode-immediate = Immediate
+gpu = GPU
+power-preference-low = Low power
+power-preference-high = High performance
+backend = Backend
+backend-auto = Automatic
+
+## Keyboard shortcuts
+
+keyboard-shortcuts = Keyboard shortcuts
+shortcut-new-entry = Type the date of a new event
+shortcut-add = Add the typed date
+shortcut-select = Select the previous or next event
+shortcut-delete = Delete the selected event
+shortcut-undo = Undo
+shortcut-redo = Redo
+shortcut-cheat-sheet = Show or hide the shortcuts
+shortcut-profiler = Show or hide the profiler
+key-enter = Enter
+key-delete = Delete
@@ -0,0 +1,360 @@
+use std::collections::HashMap;
+
+use egui::accesskit::{Action, ActionRequest, CheckedState, Node, NodeId, Role, TreeUpdate};
+
+/// A node of the AccessKit tree in the form of an Android virtual view
+#[derive(Clone, Debug, PartialEq)]
+pub struct VirtualView {
+    /// Stays the same as long as the node exists
+    pub id: i32,
+    /// `None` for the children of the root, which is the host view itself
+    pub parent: Option<i32>,
+    /// Left, top, right and bottom in physical pixels, relative to the window
+    pub bounds: [i32; 4],
+    pub label: String,
+    /// The text of a text field or the number of a slider
+    pub value: Option<String>,
+    /// The Android widget class, which decides how TalkBack announces the view
+    pub class_name: &'static str,
+    pub is_clickable: bool,
+    pub is_focusable: bool,
+    pub is_focused: bool,
+    pub checked: Option<bool>,
+}
+
+/// What a screen reader asks a virtual view to do
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VirtualViewAction {
+    Click,
+    Focus,
+}
+
+/// Flattens the AccessKit trees of egui into virtual views and maps their actions back.
+///
+/// egui sends the whole tree every frame, so every update replaces all views.
+#[derive(Default)]
+pub struct VirtualViews {
+    ids: HashMap<NodeId, i32>,
+    node_ids: HashMap<i32, NodeId>,
+    next_id: i32,
+    /// In the order of the tree, parents before their children
+    views: Vec<VirtualView>,
+}
+
+impl VirtualViews {
+    pub fn update(&mut self, update: &TreeUpdate, pixels_per_point: f32) {
+        let root = match &update.tree {
+            Some(tree) => tree.root,
+            None => return,
+        };
+        let nodes: HashMap<NodeId, &Node> =
+            update.nodes.iter().map(|(id, node)| (*id, node)).collect();
+
+        // Nodes that are gone give up their id
+        self.ids.retain(|id, _| nodes.contains_key(id));
+        self.node_ids.retain(|_, id| nodes.contains_key(id));
+        self.views.clear();
+
+        let mut stack: Vec<(NodeId, Option<i32>)> = nodes
+            .get(&root)
+            .map(|root| root.children().iter().rev().map(|id| (*id, None)).collect())
+            .unwrap_or_default();
+
+        while let Some((node_id, parent)) = stack.pop() {
+            let node = match nodes.get(&node_id) {
+                Some(node) => node,
+                None => continue,
+            };
+
+            let id = self.id(node_id);
+            stack.extend(node.children().iter().rev().map(|child| (*child, Some(id))));
+
+            self.views.push(VirtualView {
+                id,
+                parent,
+                bounds: node.bounds().map_or([0; 4], |bounds| {
+                    [bounds.x0, bounds.y0, bounds.x1, bounds.y1]
+                        .map(|coordinate| (coordinate * pixels_per_point as f64).round() as i32)
+                }),
+                label: node.name().unwrap_or_default().to_owned(),
+                value: node
+                    .value()
+                    .map(str::to_owned)
+                    .or_else(|| node.numeric_value().map(|value| value.to_string())),
+                class_name: class_name(node.role()),
+                is_clickable: node.default_action_verb().is_some(),
+                is_focusable: node.supports_action(Action::Focus),
+                is_focused: update.focus == Some(node_id),
+                checked: node.checked_state().and_then(|state| match state {
+                    CheckedState::True => Some(true),
+                    CheckedState::False => Some(false),
+                    CheckedState::Mixed => None,
+                }),
+            });
+        }
+    }
+
+    pub fn views(&self) -> &[VirtualView] {
+        &self.views
+    }
+
+    /// The AccessKit request for `action` on the view `id`, `None` if the view is gone
+    pub fn action_request(&self, id: i32, action: VirtualViewAction) -> Option<ActionRequest> {
+        Some(ActionRequest {
+            action: match action {
+                VirtualViewAction::Click => Action::Default,
+                VirtualViewAction::Focus => Action::Focus,
+            },
+            target: *self.node_ids.get(&id)?,
+            data: None,
+        })
+    }
+
+    fn id(&mut self, node_id: NodeId) -> i32 {
+        if let Some(id) = self.ids.get(&node_id) {
+            return *id;
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.ids.insert(node_id, id);
+        self.node_ids.insert(id, node_id);
+
+        id
+    }
+}
+
+fn class_name(role: Role) -> &'static str {
+    match role {
+        Role::Button | Role::ToggleButton => "android.widget.Button",
+        Role::CheckBox => "android.widget.CheckBox",
+        Role::RadioButton => "android.widget.RadioButton",
+        Role::TextField => "android.widget.EditText",
+        Role::Slider | Role::SpinButton => "android.widget.SeekBar",
+        Role::PopupButton => "android.widget.Spinner",
+        Role::StaticText => "android.widget.TextView",
+        _ => "android.view.View",
+    }
+}
+
+/// Hands the virtual views to TalkBack and its actions back to egui, as accesskit_winit has no
+/// Android adapter. The provider is `AccessibilityBridge` in `android/src`.
+#[cfg(target_os = "android")]
+#[derive(Default)]
+pub(crate) struct AndroidAccessibility {
+    /// Only while a screen reader is on, the bridge costs JNI calls every frame otherwise
+    is_attached: bool,
+    views: VirtualViews,
+    /// The views TalkBack has, to skip the JNI calls while nothing changes
+    pushed: Vec<VirtualView>,
+}
+
+#[cfg(target_os = "android")]
+static WAKE_UP: std::sync::OnceLock<Box<dyn Fn() + Send + Sync>> = std::sync::OnceLock::new();
+
+#[cfg(target_os = "android")]
+impl AndroidAccessibility {
+    const BRIDGE_CLASS: &'static str = "com.example.android_rust_experiments.AccessibilityBridge";
+    const CLICKABLE: i32 = 1;
+    const FOCUSABLE: i32 = 2;
+    const FOCUSED: i32 = 4;
+    const CHECKABLE: i32 = 8;
+    const CHECKED: i32 = 16;
+
+    /// Whether a screen reader is on, the provider is attached to the activity then.
+    ///
+    /// `wake_up` is called when TalkBack asks for an action, so a frame takes it.
+    pub(crate) fn attach(&mut self, wake_up: impl Fn() + Send + Sync + 'static) -> bool {
+        let _ = WAKE_UP.set(Box::new(wake_up));
+
+        let result = crate::platform::with_activity(|env, activity| {
+            use jni::{objects::JValue, NativeMethod};
+
+            let class = crate::platform::load_class(env, activity, Self::BRIDGE_CLASS)?;
+            env.register_native_methods(
+                &class,
+                &[NativeMethod {
+                    name: "requestFrame".into(),
+                    sig: "()V".into(),
+                    fn_ptr: request_frame as *mut std::ffi::c_void,
+                }],
+            )?;
+
+            let is_enabled = env
+                .call_static_method(
+                    &class,
+                    "isEnabled",
+                    "(Landroid/content/Context;)Z",
+                    &[JValue::Object(activity)],
+                )?
+                .z()?;
+            if is_enabled {
+                env.call_static_method(
+                    &class,
+                    "attach",
+                    "(Landroid/app/Activity;)V",
+                    &[JValue::Object(activity)],
+                )?;
+            }
+
+            Ok(is_enabled)
+        });
+
+        self.pushed.clear();
+        self.is_attached = result.unwrap_or_else(|err| {
+            log::warn!("Failed to attach the accessibility bridge: {}", err);
+            false
+        });
+
+        self.is_attached
+    }
+
+    pub(crate) fn update(&mut self, update: &TreeUpdate, pixels_per_point: f32) {
+        if !self.is_attached {
+            return;
+        }
+
+        self.views.update(update, pixels_per_point);
+
+        if self.views.views() == self.pushed.as_slice() {
+            return;
+        }
+
+        if let Err(err) = crate::platform::with_activity(|env, activity| {
+            push_views(env, activity, self.views.views())
+        }) {
+            log::warn!("Failed to update the accessibility bridge: {}", err);
+        }
+
+        self.pushed = self.views.views().to_vec();
+    }
+
+    /// The actions TalkBack asked for since the last call
+    pub(crate) fn take_action_requests(&self) -> Vec<ActionRequest> {
+        if !self.is_attached {
+            return Vec::new();
+        }
+
+        let actions = crate::platform::with_activity(|env, activity| {
+            use jni::objects::JIntArray;
+
+            let class = crate::platform::load_class(env, activity, Self::BRIDGE_CLASS)?;
+            let actions = JIntArray::from(
+                env.call_static_method(&class, "takeActions", "()[I", &[])?
+                    .l()?,
+            );
+
+            let mut buffer = vec![0; env.get_array_length(&actions)? as usize];
+            env.get_int_array_region(&actions, 0, &mut buffer)?;
+
+            Ok(buffer)
+        });
+
+        let actions = actions.unwrap_or_else(|err| {
+            log::warn!("Failed to read the accessibility actions: {}", err);
+            Vec::new()
+        });
+
+        // Pairs of a view id and 0 for a click or 1 for focus
+        actions
+            .chunks_exact(2)
+            .filter_map(|action| {
+                let kind = match action[1] {
+                    0 => VirtualViewAction::Click,
+                    _ => VirtualViewAction::Focus,
+                };
+
+                self.views.action_request(action[0], kind)
+            })
+            .collect()
+    }
+}
+
+#[cfg(target_os = "android")]
+fn push_views(
+    env: &mut jni::JNIEnv,
+    activity: &jni::objects::JObject,
+    views: &[VirtualView],
+) -> jni::errors::Result<()> {
+    use jni::objects::{JObject, JValue};
+
+    let length = views.len() as i32;
+
+    let ids = int_array(env, views.iter().map(|view| view.id).collect())?;
+    let parents = int_array(
+        env,
+        views.iter().map(|view| view.parent.unwrap_or(-1)).collect(),
+    )?;
+    let bounds = int_array(env, views.iter().flat_map(|view| view.bounds).collect())?;
+    let flags = int_array(
+        env,
+        views
+            .iter()
+            .map(|view| {
+                let flag = |is_set: bool, flag: i32| if is_set { flag } else { 0 };
+
+                flag(view.is_clickable, AndroidAccessibility::CLICKABLE)
+                    | flag(view.is_focusable, AndroidAccessibility::FOCUSABLE)
+                    | flag(view.is_focused, AndroidAccessibility::FOCUSED)
+                    | flag(view.checked.is_some(), AndroidAccessibility::CHECKABLE)
+                    | flag(view.checked == Some(true), AndroidAccessibility::CHECKED)
+            })
+            .collect(),
+    )?;
+
+    let labels = env.new_object_array(length, "java/lang/String", JObject::null())?;
+    let values = env.new_object_array(length, "java/lang/String", JObject::null())?;
+    let class_names = env.new_object_array(length, "java/lang/String", JObject::null())?;
+
+    for (index, view) in views.iter().enumerate() {
+        let index = index as i32;
+
+        let label = env.new_string(&view.label)?;
+        env.set_object_array_element(&labels, index, &label)?;
+
+        if let Some(value) = &view.value {
+            let value = env.new_string(value)?;
+            env.set_object_array_element(&values, index, &value)?;
+        }
+
+        let class_name = env.new_string(view.class_name)?;
+        env.set_object_array_element(&class_names, index, &class_name)?;
+    }
+
+    let class = crate::platform::load_class(env, activity, AndroidAccessibility::BRIDGE_CLASS)?;
+    env.call_static_method(
+        &class,
+        "update",
+        "([I[I[I[Ljava/lang/String;[Ljava/lang/String;[Ljava/lang/String;[I)V",
+        &[
+            JValue::Object(&ids),
+            JValue::Object(&parents),
+            JValue::Object(&bounds),
+            JValue::Object(&labels),
+            JValue::Object(&values),
+            JValue::Object(&class_names),
+            JValue::Object(&flags),
+        ],
+    )?;
+
+    Ok(())
+}
+
+/// `AccessibilityBridge.requestFrame`, called on the UI thread after TalkBack asked for an action
+#[cfg(target_os = "android")]
+extern "system" fn request_frame(_env: jni::JNIEnv, _class: jni::objects::JClass) {
+    if let Some(wake_up) = WAKE_UP.get() {
+        wake_up();
+    }
+}
+
+#[cfg(target_os = "android")]
+fn int_array<'local>(
+    env: &mut jni::JNIEnv<'local>,
+    values: Vec<i32>,
+) -> jni::errors::Result<jni::objects::JIntArray<'local>> {
+    let array = env.new_int_array(values.len() as i32)?;
+    env.set_int_array_region(&array, 0, &values)?;
+
+    Ok(array)
+}
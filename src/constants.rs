@@ -3,3 +3,11 @@ pub(crate) const STORAGE_PATH: &str = "/data/data/com.example.android_rust_exper
 
 #[cfg(not(target_os = "android"))]
 pub(crate) const STORAGE_PATH: &str = ".";
+
+/// The tracker the events belong to until the settings pick another one
+pub(crate) const DEFAULT_TRACKER: &str = "default";
+
+/// The intent action that logs an event for today without showing the UI, used by the app
+/// shortcut
+#[cfg(target_os = "android")]
+pub(crate) const LOG_TODAY_ACTION: &str = "com.example.android_rust_experiments.LOG_TODAY";
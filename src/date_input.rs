@@ -0,0 +1,62 @@
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+
+use crate::{i18n::Locale, settings::DateFormat};
+
+/// Reads a typed date, ignoring the case: "today", "yesterday", "tomorrow", weekdays like
+/// "friday" (today or before) or "last friday" (before today), "3 days ago", "a week ago",
+/// "2 months ago", ISO dates like "2024-03-05" and dates in `date_format`, whose
+/// `Automatic` reads the numeric dates of `locale`
+pub fn parse_date(
+    text: &str,
+    today: NaiveDate,
+    date_format: DateFormat,
+    locale: &Locale,
+) -> Option<NaiveDate> {
+    let text = text.trim().to_lowercase();
+    let words: Vec<&str> = text.split_whitespace().collect();
+
+    match words.as_slice() {
+        ["today"] => return Some(today),
+        ["yesterday"] => return today.pred_opt(),
+        ["tomorrow"] => return today.succ_opt(),
+        ["last", weekday] => return Some(previous(weekday.parse().ok()?, today, false)),
+        [count, unit, "ago"] => return ago(count, unit, today),
+        [weekday] => {
+            if let Ok(weekday) = weekday.parse() {
+                return Some(previous(weekday, today, true));
+            }
+        }
+        _ => {}
+    }
+
+    NaiveDate::parse_from_str(&text, "%Y-%m-%d")
+        .ok()
+        .or_else(|| date_format.parse(&text, locale))
+}
+
+/// The last `weekday` before `today`, or on it if `includes_today`
+fn previous(weekday: Weekday, today: NaiveDate, includes_today: bool) -> NaiveDate {
+    let days = (7 + today.weekday().num_days_from_monday() - weekday.num_days_from_monday()) % 7;
+
+    let days = match days {
+        0 if !includes_today => 7,
+        days => days,
+    };
+
+    today - Days::new(days as u64)
+}
+
+fn ago(count: &str, unit: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let count: u32 = match count {
+        "a" | "an" | "one" => 1,
+        count => count.parse().ok()?,
+    };
+
+    match unit.trim_end_matches('s') {
+        "day" => today.checked_sub_days(Days::new(count as u64)),
+        "week" => today.checked_sub_days(Days::new(7 * count as u64)),
+        "month" => today.checked_sub_months(Months::new(count)),
+        "year" => today.checked_sub_months(Months::new(12 * count)),
+        _ => None,
+    }
+}
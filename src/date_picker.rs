@@ -0,0 +1,504 @@
+use std::{hash::Hash, ops::RangeInclusive};
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use egui::{
+    accesskit::Action, Align2, Button, Id, Key, Modifiers, Response, SelectableLabel, Sense,
+    TextStyle, Ui, Widget, WidgetInfo,
+};
+
+use crate::{helper::set_accessible_name, i18n::Locale};
+
+/// Rows a wheel shows, the picked value in the middle
+const VISIBLE_ROWS: i32 = 3;
+/// How fast a flicked wheel slows down, per second
+const FRICTION: f32 = 4.0;
+/// Rows per second below which a flicked wheel stops
+const MIN_VELOCITY: f32 = 0.5;
+/// How fast a wheel settles on the picked value, per second
+const SNAP_SPEED: f32 = 12.0;
+
+/// Picks a date with wheels for the year, the month and the day, or from a calendar popup.
+///
+/// The days follow the length of the month, and nothing outside `min` and `max` can be picked.
+pub struct DatePicker<'a> {
+    date: &'a mut NaiveDate,
+    id_source: Id,
+    min: NaiveDate,
+    max: NaiveDate,
+    week_start: Weekday,
+    text_style: TextStyle,
+    has_calendar: bool,
+    locale: &'a Locale,
+}
+
+impl<'a> DatePicker<'a> {
+    pub fn new(id_source: impl Hash, date: &'a mut NaiveDate) -> Self {
+        Self {
+            date,
+            id_source: Id::new(id_source),
+            min: NaiveDate::MIN,
+            max: NaiveDate::MAX,
+            week_start: Weekday::Mon,
+            text_style: TextStyle::Button,
+            has_calendar: true,
+            locale: Locale::english(),
+        }
+    }
+
+    /// The earliest date that can be picked
+    pub fn min(mut self, min: NaiveDate) -> Self {
+        self.min = min;
+        self
+    }
+
+    /// The latest date that can be picked
+    pub fn max(mut self, max: NaiveDate) -> Self {
+        self.max = max;
+        self
+    }
+
+    /// The first column of the calendar
+    pub fn week_start(mut self, week_start: Weekday) -> Self {
+        self.week_start = week_start;
+        self
+    }
+
+    /// The text style of the wheels
+    pub fn text_style(mut self, text_style: TextStyle) -> Self {
+        self.text_style = text_style;
+        self
+    }
+
+    /// Whether a button next to the wheels opens a calendar, on by default
+    pub fn calendar(mut self, has_calendar: bool) -> Self {
+        self.has_calendar = has_calendar;
+        self
+    }
+
+    /// The language of the month and weekday names, English by default
+    pub fn locale(mut self, locale: &'a Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+}
+
+impl Widget for DatePicker<'_> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let id = ui.make_persistent_id(self.id_source);
+        let (min, max) = (self.min, self.max.max(self.min));
+        let mut date = (*self.date).clamp(min, max);
+        let locale = self.locale;
+
+        let mut response = ui
+            .horizontal(|ui| {
+                let mut year = date.year();
+                let year_wheel = wheel(
+                    ui,
+                    id.with("year"),
+                    &locale.text("year"),
+                    &mut year,
+                    min.year()..=max.year(),
+                    &self.text_style,
+                    |year| year.to_string(),
+                );
+                date = clamped_date(year, date.month(), date.day()).clamp(min, max);
+
+                let first_month = if date.year() == min.year() {
+                    min.month()
+                } else {
+                    1
+                };
+                let last_month = if date.year() == max.year() {
+                    max.month()
+                } else {
+                    12
+                };
+                let mut month = date.month() as i32;
+                let month_wheel = wheel(
+                    ui,
+                    id.with("month"),
+                    &locale.text("month"),
+                    &mut month,
+                    first_month as i32..=last_month as i32,
+                    &self.text_style,
+                    |month| locale.short_month_name(month as u32),
+                );
+                date = clamped_date(date.year(), month as u32, date.day()).clamp(min, max);
+
+                let is_first_month = (date.year(), date.month()) == (min.year(), min.month());
+                let is_last_month = (date.year(), date.month()) == (max.year(), max.month());
+                let first_day = if is_first_month { min.day() } else { 1 };
+                let last_day = if is_last_month {
+                    max.day()
+                } else {
+                    days_in_month(date.year(), date.month())
+                };
+                let mut day = date.day() as i32;
+                let day_wheel = wheel(
+                    ui,
+                    id.with("day"),
+                    &locale.text("day"),
+                    &mut day,
+                    first_day as i32..=last_day as i32,
+                    &self.text_style,
+                    |day| day.to_string(),
+                );
+                date = clamped_date(date.year(), date.month(), day as u32);
+
+                let mut response = year_wheel | month_wheel | day_wheel;
+
+                if self.has_calendar {
+                    response |= calendar_button(
+                        ui,
+                        id.with("calendar"),
+                        &mut date,
+                        min,
+                        max,
+                        self.week_start,
+                        locale,
+                    );
+                }
+
+                response
+            })
+            .inner;
+
+        if date != *self.date {
+            *self.date = date;
+            response.mark_changed();
+        }
+
+        response
+    }
+}
+
+/// How far a wheel is scrolled away from its value, in rows
+#[derive(Clone, Copy, Default)]
+struct WheelState {
+    offset: f32,
+    /// Rows per second, while a flick is still moving
+    velocity: f32,
+}
+
+/// A column of values that is dragged or flicked like a spinner wheel.
+///
+/// The arrow keys and screen readers step the value by one, a click picks the clicked row.
+fn wheel(
+    ui: &mut Ui,
+    id: Id,
+    name: &str,
+    value: &mut i32,
+    range: RangeInclusive<i32>,
+    text_style: &TextStyle,
+    format: impl Fn(i32) -> String,
+) -> Response {
+    let font_id = text_style.resolve(ui.style());
+    let padding = ui.spacing().button_padding;
+    let row_height = ui.fonts(|fonts| fonts.row_height(&font_id)) + 2.0 * padding.y;
+
+    // Short ranges like the months are measured completely, long ones only at their ends
+    let measured: Vec<i32> = if range.end() - range.start() <= 31 {
+        range.clone().collect()
+    } else {
+        vec![*range.start(), *range.end()]
+    };
+    let text_width = ui.fonts(|fonts| {
+        measured
+            .iter()
+            .map(|value| {
+                fonts
+                    .layout_no_wrap(format(*value), font_id.clone(), Default::default())
+                    .size()
+                    .x
+            })
+            .fold(0.0, f32::max)
+    });
+
+    let size = egui::vec2(
+        text_width + 4.0 * padding.x,
+        row_height * VISIBLE_ROWS as f32,
+    );
+    let (rect, _) = ui.allocate_exact_size(size, Sense::hover());
+    let mut response = ui.interact(rect, id, Sense::click_and_drag());
+
+    let mut state: WheelState = ui.data(|data| data.get_temp(id)).unwrap_or_default();
+    let dt = ui.input(|i| i.stable_dt).clamp(0.001, 0.1);
+
+    if response.dragged() {
+        let rows = -response.drag_delta().y / row_height;
+        state.offset += rows;
+        // Smoothed, so one uneven frame at the end doesn't decide the flick
+        state.velocity = 0.5 * state.velocity + 0.5 * rows / dt;
+    } else if state.velocity.abs() > MIN_VELOCITY {
+        state.offset += state.velocity * dt;
+        state.velocity *= (-FRICTION * dt).exp();
+    } else {
+        state.velocity = 0.0;
+        state.offset *= (-SNAP_SPEED * dt).exp();
+
+        if state.offset.abs() < 0.01 {
+            state.offset = 0.0;
+        }
+    }
+
+    if response.hovered() {
+        state.offset -= ui.input(|i| i.scroll_delta.y) / row_height;
+    }
+
+    let mut steps = 0;
+
+    if response.clicked() {
+        if let Some(position) = response.interact_pointer_pos() {
+            steps += ((position.y - rect.center().y) / row_height).round() as i32;
+        }
+    }
+
+    if response.has_focus() {
+        steps += ui.input_mut(|i| {
+            i.consume_key(Modifiers::NONE, Key::ArrowUp) as i32
+                - i.consume_key(Modifiers::NONE, Key::ArrowDown) as i32
+        });
+    }
+
+    steps += ui.input(|i| {
+        i.num_accesskit_action_requests(id, Action::Increment) as i32
+            - i.num_accesskit_action_requests(id, Action::Decrement) as i32
+    });
+
+    // Every whole row of scrolling moves on to the next value
+    let scrolled = state.offset.round();
+    state.offset -= scrolled;
+    steps += scrolled as i32;
+
+    let new_value = (*value + steps).clamp(*range.start(), *range.end());
+
+    if new_value != *value + steps {
+        state.velocity = 0.0;
+    }
+
+    // The wheel can't be scrolled past its ends
+    if new_value == *range.start() {
+        state.offset = state.offset.max(0.0);
+    }
+
+    if new_value == *range.end() {
+        state.offset = state.offset.min(0.0);
+    }
+
+    if new_value != *value {
+        *value = new_value;
+        response.mark_changed();
+    }
+
+    if state.offset != 0.0 || state.velocity != 0.0 {
+        ui.ctx().request_repaint();
+    }
+
+    ui.data_mut(|data| data.insert_temp(id, state));
+
+    if ui.is_rect_visible(rect) {
+        let visuals = ui.style().interact(&response);
+        let painter = ui.painter_at(rect);
+
+        painter.rect(
+            rect,
+            visuals.rounding,
+            ui.visuals().extreme_bg_color,
+            visuals.bg_stroke,
+        );
+
+        let band =
+            egui::Rect::from_center_size(rect.center(), egui::vec2(rect.width(), row_height));
+        painter.rect_filled(band, visuals.rounding, visuals.bg_fill);
+
+        let rows = VISIBLE_ROWS / 2 + 1;
+
+        for row in -rows..=rows {
+            let shown = *value + row;
+
+            if !range.contains(&shown) {
+                continue;
+            }
+
+            let y = rect.center().y + (row as f32 - state.offset) * row_height;
+            let distance = (y - rect.center().y).abs() / row_height;
+            let color = visuals
+                .text_color()
+                .gamma_multiply((1.0 - 0.5 * distance).max(0.2));
+
+            painter.text(
+                egui::pos2(rect.center().x, y),
+                Align2::CENTER_CENTER,
+                format(shown),
+                font_id.clone(),
+                color,
+            );
+        }
+
+        if response.has_focus() {
+            painter.rect_stroke(rect, visuals.rounding, ui.visuals().selection.stroke);
+        }
+    }
+
+    response.widget_info(|| {
+        let mut info = WidgetInfo::drag_value(*value as f64);
+        info.label = Some(name.to_owned());
+        info.current_text_value = Some(format(*value));
+        info
+    });
+    ui.ctx().accesskit_node_builder(id, |builder| {
+        builder.add_action(Action::Increment);
+        builder.add_action(Action::Decrement);
+    });
+
+    response
+}
+
+/// A button that opens a month calendar below the wheels, picking a day closes it
+fn calendar_button(
+    ui: &mut Ui,
+    id: Id,
+    date: &mut NaiveDate,
+    min: NaiveDate,
+    max: NaiveDate,
+    week_start: Weekday,
+    locale: &Locale,
+) -> Response {
+    let button = ui.button("📅");
+    set_accessible_name(&button, locale.text("calendar"));
+
+    // The first day of the shown month, while the calendar is open
+    let mut shown_month: Option<NaiveDate> = ui.data(|data| data.get_temp(id));
+
+    if button.clicked() {
+        shown_month = match shown_month {
+            Some(_) => None,
+            None => date.with_day(1),
+        };
+    }
+
+    if let Some(month) = &mut shown_month {
+        let area = egui::Area::new(id)
+            .order(egui::Order::Foreground)
+            .fixed_pos(button.rect.left_bottom())
+            .constrain(true)
+            .show(ui.ctx(), |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    calendar_ui(ui, month, date, min, max, week_start, locale)
+                })
+            });
+
+        // The response of the area misses the move back onto the screen, the frame's doesn't
+        let popup = area.inner;
+        let is_closed = popup.inner
+            || ui.input(|i| i.key_pressed(Key::Escape))
+            || (popup.response.clicked_elsewhere() && !button.clicked());
+
+        if is_closed {
+            shown_month = None;
+        }
+    }
+
+    ui.data_mut(|data| match shown_month {
+        Some(month) => data.insert_temp(id, month),
+        None => {
+            data.remove::<NaiveDate>(id);
+        }
+    });
+
+    button
+}
+
+/// The days of `month` with buttons to the neighbouring months, returns whether a day was picked
+fn calendar_ui(
+    ui: &mut Ui,
+    month: &mut NaiveDate,
+    date: &mut NaiveDate,
+    min: NaiveDate,
+    max: NaiveDate,
+    week_start: Weekday,
+    locale: &Locale,
+) -> bool {
+    let next_month = clamped_date(month.year(), month.month(), 31).succ_opt();
+
+    ui.horizontal(|ui| {
+        let previous = ui.add_enabled(*month > min, Button::new("◀"));
+        set_accessible_name(&previous, locale.text("previous-month"));
+
+        ui.label(locale.format_month(*month));
+
+        let next = ui.add_enabled(
+            next_month.is_some_and(|next_month| next_month <= max),
+            Button::new("▶"),
+        );
+        set_accessible_name(&next, locale.text("next-month"));
+
+        if previous.clicked() {
+            if let Some(previous_month) = month.pred_opt().and_then(|last| last.with_day(1)) {
+                *month = previous_month;
+            }
+        }
+
+        if next.clicked() {
+            if let Some(next_month) = next_month {
+                *month = next_month;
+            }
+        }
+    });
+
+    let mut is_picked = false;
+
+    egui::Grid::new("calendar_days").show(ui, |ui| {
+        let mut weekday = week_start;
+
+        for _ in 0..7 {
+            ui.label(locale.short_weekday_name(weekday));
+            weekday = weekday.succ();
+        }
+
+        ui.end_row();
+
+        let blanks =
+            (7 + month.weekday().num_days_from_monday() - week_start.num_days_from_monday()) % 7;
+
+        for _ in 0..blanks {
+            ui.label("");
+        }
+
+        for day in month
+            .iter_days()
+            .take_while(|day| day.month() == month.month())
+        {
+            let label = SelectableLabel::new(day == *date, day.day().to_string());
+            let response = ui.add_enabled((min..=max).contains(&day), label);
+            set_accessible_name(&response, locale.format_long_date(day));
+
+            if response.clicked() {
+                *date = day;
+                is_picked = true;
+            }
+
+            if (blanks + day.day()).is_multiple_of(7) {
+                ui.end_row();
+            }
+        }
+    });
+
+    is_picked
+}
+
+/// The date, with the day moved back to the last one of shorter months
+fn clamped_date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day.min(days_in_month(year, month))).unwrap()
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = match month {
+        12 => (year + 1, 1),
+        month => (year, month + 1),
+    };
+
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|first| first.pred_opt())
+        .map_or(31, |last| last.day())
+}
@@ -1,301 +1,384 @@
-use std::ops::Mul;
-
-use chrono::{Datelike, Local};
-use egui::{Label, SelectableLabel, Sense, TextStyle, WidgetText};
-use itertools::Itertools;
+use chrono::{Datelike, NaiveDate};
+use egui::{Key, Label, Modifiers, SelectableLabel, TextEdit, TextStyle};
 
 use crate::{
-    helper::{Demo, View},
-    io::{read_from_file, write_to_file},
+    constants::DEFAULT_TRACKER,
+    date_input::parse_date,
+    date_picker::DatePicker,
+    events::Event,
+    helper::{set_accessible_name, StateView},
+    shortcuts,
+    state::{AppState, Message, MessageBus},
 };
 
+/// The list of events and the date picker to add more
+#[derive(Default)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[cfg_attr(feature = "serde", serde(default))]
 pub struct Dates {
-    pub dates: Vec<(i32, u32, u32)>,
-    selected_year: i32,
-    selected_month: u32,
-    selected_day: u32,
-    pub number_of_consecutive_months: usize,
-    pub number_of_events_last_year: usize,
+    /// The date in the picker, today until another one is picked
+    picked: Option<NaiveDate>,
+    /// The event whose deletion waits for confirmation
+    pending_delete: Option<Event>,
+    /// The event the desktop layout shows next to the list
+    selected: Option<NaiveDate>,
+    /// Which of the events on the selected date is selected, in the order of the list
+    selected_nth: usize,
+    /// The text of the date field
+    typed_date: String,
+    /// Whether a row of the list had the keyboard focus in the last frame
+    is_row_focused: bool,
+    /// The scroll offset and height of the list in the last frame
+    list_scroll: (f32, f32),
 }
 
-impl Default for Dates {
-    fn default() -> Self {
-        let mut dates = Self {
-            dates: read_from_file(),
-            selected_year: 0,
-            selected_month: 0,
-            selected_day: 0,
-            number_of_consecutive_months: 0,
-            number_of_events_last_year: 0,
-        };
+impl StateView for Dates {
+    fn ui(&mut self, ui: &mut egui::Ui, state: &AppState, bus: &mut MessageBus) {
+        let screen_size = ui.ctx().input(|i| i.screen_rect.size());
 
-        if !dates.dates.is_empty() {
-            dates.sort_by_date();
-            dates.calculate_consecutive_months();
-            dates.calculate_number_of_events_last_year();
-        }
+        self.list_ui(ui, state, screen_size.y / 2f32, false);
+        self.confirm_delete_ui(ui, state, bus);
+        self.picker_ui(ui, state, bus);
 
-        dates
-    }
-}
+        let statistics = state.statistics();
+        let locale = state.locale();
 
-impl Demo for Dates {
-    fn name(&self) -> &'static str {
-        "Dates"
-    }
+        ui.label(locale.text_with(
+            "consecutive-months",
+            &[("count", statistics.consecutive_months.into())],
+        ));
 
-    fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
-        egui::Window::new(self.name())
-            .default_width(320.0)
-            .open(open)
-            .show(ctx, |ui| {
-                use View as _;
-                self.ui(ui);
-            });
+        ui.label(locale.text_with(
+            "recent-events",
+            &[
+                ("months", state.settings().statistic_months.into()),
+                ("count", statistics.recent_events.into()),
+            ],
+        ));
     }
 }
 
-impl View for Dates {
-    fn ui(&mut self, ui: &mut egui::Ui) {
-        let screen_size = ui.ctx().input(|i| i.screen_rect.size());
+impl Dates {
+    /// The events, `is_selectable` lets a click select one for `detail_ui`
+    pub(crate) fn list_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        state: &AppState,
+        max_height: f32,
+        is_selectable: bool,
+    ) {
+        let row_height = ui
+            .text_style_height(&TextStyle::Body)
+            .max(ui.text_style_height(&TextStyle::Button) + 2.0 * ui.spacing().button_padding.y)
+            .max(ui.spacing().interact_size.y);
+
+        let mut removed_event = None;
+        let mut scroll_area = egui::ScrollArea::vertical()
+            .max_height(max_height)
+            .stick_to_right(true);
+
+        // The arrows would otherwise move the focus or step a focused picker as well
+        let is_navigable =
+            is_selectable && (self.is_row_focused || ui.memory(|memory| memory.focus().is_none()));
+
+        if is_navigable {
+            let (step, is_delete) = ui.input_mut(|i| {
+                (
+                    i.consume_key(Modifiers::NONE, Key::ArrowDown) as i32
+                        - i.consume_key(Modifiers::NONE, Key::ArrowUp) as i32,
+                    i.consume_key(Modifiers::NONE, Key::Delete),
+                )
+            });
+
+            if step != 0 {
+                if let Some(index) = self.select_next(state, step) {
+                    let stride = row_height + ui.spacing().item_spacing.y;
+                    let (offset, height) = self.list_scroll;
+                    let top = index as f32 * stride;
+
+                    if top < offset {
+                        scroll_area = scroll_area.vertical_scroll_offset(top);
+                    } else if top + stride > offset + height {
+                        scroll_area = scroll_area.vertical_scroll_offset(top + stride - height);
+                    }
+                }
+            }
+
+            if is_delete {
+                removed_event = self.selected_event(state).cloned();
+            }
+        }
+
+        let mut is_row_focused = false;
+        let selected_index = self.selected_index(state);
 
-        let mut has_dates_changed = false;
-        let mut is_input_add_request = false;
+        // Only the rows inside the visible part of the scroll area are laid out
+        let output =
+            scroll_area.show_rows(ui, row_height, state.events().len(), |ui, row_range| {
+                for (index, event) in row_range
+                    .clone()
+                    .zip(state.events().iter_from(row_range.start))
+                {
+                    let date = event.date;
 
-        egui::ScrollArea::vertical()
-            .max_height(screen_size.y / 2f32)
-            .stick_to_right(true)
-            .show(ui, |ui| {
-                for (index, date) in &mut self.dates.clone().into_iter().enumerate() {
                     ui.horizontal(|ui| {
-                        let label = Label::new(format!("{}-{}-{}", date.0, date.1, date.2));
+                        let mut text = state.format_date(date);
+                        if event.tracker != DEFAULT_TRACKER {
+                            text = format!("{} · {}", text, event.tracker);
+                        }
+                        let size = [ui.available_width() - 50.0, row_height];
 
-                        let _input = ui.add_sized(
-                            [ui.available_width() - 50.0, ui.spacing().interact_size.y],
-                            label,
-                        );
+                        if is_selectable {
+                            let label = SelectableLabel::new(selected_index == Some(index), text);
+
+                            let response = ui.add_sized(size, label);
+                            is_row_focused |= response.has_focus();
+
+                            if response.clicked() {
+                                self.select(state, index);
+                            }
+                        } else {
+                            ui.add_sized(size, Label::new(text));
+                        }
 
                         let delete = ui.button("x");
+                        is_row_focused |= delete.has_focus();
+                        set_accessible_name(
+                            &delete,
+                            state.locale().text_with(
+                                "delete-event",
+                                &[("date", state.format_date(date).into())],
+                            ),
+                        );
+
+                        // Rows outside the visible part do not exist, so keep the focused one
+                        // in view for tabbing through the list
+                        if delete.gained_focus() {
+                            delete.scroll_to_me(None);
+                        }
+
                         delete.clicked().then(|| {
-                            has_dates_changed = true;
-                            self.dates.remove(index);
+                            removed_event = Some(event.clone());
                         });
                     });
                 }
             });
 
-        let mut selected_year = if self.selected_year == 0 {
-            Local::now().year()
-        } else {
-            self.selected_year
-        };
-
-        let mut selected_month = if self.selected_month == 0 {
-            Local::now().month()
-        } else {
-            self.selected_month
-        };
-
-        let mut selected_day = if self.selected_day == 0 {
-            Local::now().day()
-        } else {
-            self.selected_day
-        };
-
-        ui.horizontal(|ui| {
-            ui.set_height(100.0);
-
-            let _year_input = egui::ComboBox::from_id_source(0)
-                .selected_text(
-                    <WidgetText>::from(selected_year.to_string())
-                        .text_style(TextStyle::Name("DateInputButton".into())),
-                )
-                .show_ui(ui, |ui| {
-                    let current_year = Local::now().year();
-                    for year in ((current_year - 15)..=current_year).rev() {
-                        add_selectable_draggable_label(&mut selected_year, year, ui);
-                    }
-                });
+        self.is_row_focused = is_row_focused;
+        self.list_scroll = (output.state.offset.y, output.inner_rect.height());
 
-            self.selected_year = selected_year;
+        if removed_event.is_some() {
+            self.pending_delete = removed_event;
+        }
+    }
 
-            let _month_input = egui::ComboBox::from_id_source(1)
-                .selected_text(
-                    <WidgetText>::from(selected_month.to_string())
-                        .text_style(TextStyle::Name("DateInputButton".into())),
-                )
-                .show_ui(ui, |ui| {
-                    for month in 1..=12 {
-                        add_selectable_draggable_label(&mut selected_month, month, ui);
-                    }
-                });
+    /// The row of the selected event, if it still exists
+    fn selected_index(&self, state: &AppState) -> Option<usize> {
+        let events = state.events();
+        let date = self.selected?;
+        let count = events.count(date..=date);
 
-            self.selected_month = selected_month;
+        (count > 0).then(|| events.index_of(date) + self.selected_nth.min(count - 1))
+    }
 
-            let _day_input = egui::ComboBox::from_id_source(2)
-                .selected_text(
-                    <WidgetText>::from(selected_day.to_string())
-                        .text_style(TextStyle::Name("DateInputButton".into())),
-                )
-                .show_ui(ui, |ui| {
-                    for day in 1..=31 {
-                        add_selectable_draggable_label(&mut selected_day, day, ui);
-                    }
-                });
+    fn selected_event<'a>(&self, state: &'a AppState) -> Option<&'a Event> {
+        let index = self.selected_index(state)?;
+        state.events().iter_from(index).next()
+    }
 
-            self.selected_day = selected_day;
-        });
+    fn select(&mut self, state: &AppState, index: usize) {
+        let events = state.events();
+        self.selected = events.iter_from(index).next().map(|event| event.date);
+        self.selected_nth = self
+            .selected
+            .map_or(0, |date| index - events.index_of(date));
+    }
 
-        ui.button("+").clicked().then(|| {
-            is_input_add_request = true;
-        });
+    /// Moves the selection `step` events down the list, returns the index of the selected one
+    fn select_next(&mut self, state: &AppState, step: i32) -> Option<usize> {
+        let events = state.events();
 
-        if is_input_add_request {
-            has_dates_changed = true;
-            self.dates
-                .push((selected_year, selected_month, selected_day));
+        if events.is_empty() {
+            return None;
         }
 
-        if has_dates_changed {
-            self.sort_by_date();
-            self.calculate_consecutive_months();
-            self.calculate_number_of_events_last_year();
-            write_to_file(self.dates.clone());
-        }
+        let index = match self.selected_index(state) {
+            Some(index) => (index as i32 + step).clamp(0, events.len() as i32 - 1) as usize,
+            None if step > 0 => 0,
+            None => events.len() - 1,
+        };
 
-        ui.label(format!(
-            "Consecutive months: {}",
-            self.number_of_consecutive_months
-        ));
+        self.select(state, index);
 
-        ui.label(format!(
-            "Events last year: {}",
-            self.number_of_events_last_year
-        ));
+        Some(index)
     }
-}
 
-fn add_selectable_draggable_label<T: Copy + Clone + PartialEq + ToString>(
-    selected_value: &mut T,
-    value: T,
-    ui: &mut egui::Ui,
-) {
-    let selectable_label = SelectableLabel::new(*selected_value == value, value.to_string());
+    /// A field for typed dates like "yesterday" or "last friday", Enter adds the date
+    pub(crate) fn entry_ui(&mut self, ui: &mut egui::Ui, state: &AppState, bus: &mut MessageBus) {
+        let locale = state.locale();
+        let parsed = parse_date(
+            &self.typed_date,
+            state.today(),
+            state.settings().date_format,
+            locale,
+        );
 
-    let mut response = ui.add(selectable_label);
+        let mut is_submitted = false;
 
-    let _ = response.interact(Sense::click_and_drag());
+        ui.horizontal(|ui| {
+            let response = ui.add(
+                TextEdit::singleline(&mut self.typed_date)
+                    .id_source("typed_date")
+                    .hint_text(locale.text("new-event-date-hint")),
+            );
+            set_accessible_name(&response, locale.text("new-event-date"));
+
+            if ui.input_mut(|i| i.consume_shortcut(&shortcuts::NEW_ENTRY)) {
+                response.request_focus();
+            }
 
-    if response.clicked() && *selected_value != value {
-        *selected_value = value;
-        response.mark_changed();
-    }
+            is_submitted = response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
 
-    if response.dragged() {
-        let drag_delta = response.drag_delta();
+            if is_submitted && parsed.is_some() {
+                // Keeps typing possible for the next date
+                response.request_focus();
+            }
 
-        if drag_delta.y.abs() > 0.0 {
-            ui.scroll_with_delta(drag_delta.mul(0.75));
+            if !self.typed_date.trim().is_empty() {
+                ui.label(match parsed {
+                    Some(date) => state.format_date(date),
+                    None => locale.text("unknown-date"),
+                });
+            }
+        });
+
+        if let (true, Some(date)) = (is_submitted, parsed) {
+            let tracker = &state.settings().default_tracker;
+            bus.send(Message::EventAdded(Event::of_tracker(date, tracker)));
+            self.typed_date.clear();
+            // The new event comes after the others on its date
+            self.selected = Some(date);
+            self.selected_nth = state.events().count(date..=date);
         }
     }
-}
 
-impl Dates {
-    /// Calculates in how many consecutive months the user has been active
-    fn calculate_consecutive_months(&mut self) {
-        self.sort_by_date();
+    /// The event selected in the list
+    pub(crate) fn detail_ui(&mut self, ui: &mut egui::Ui, state: &AppState) {
+        let events = state.events();
+        let locale = state.locale();
 
-        let mut conesecutive_months = 0;
+        let date = match self.selected {
+            Some(date) if events.count(date..=date) > 0 => date,
+            _ => {
+                ui.label(locale.text("select-event"));
+                return;
+            }
+        };
 
-        let current_date = Local::now();
-        let current_year = current_date.year();
-        let current_month = current_date.month();
+        ui.heading(state.format_date(date));
+        ui.label(locale.weekday_name(date.weekday()));
 
-        let (last_year, last_month, _last_day) = self.dates.last().unwrap();
+        let count = events.count(date..=date);
+        ui.label(locale.text_with("events-on-day", &[("count", count.into())]));
 
-        let month_difference = current_month as i32 - *last_month as i32;
-        let year_difference = current_year - last_year;
+        let days_ago = (state.today() - date).num_days();
+        ui.label(match days_ago {
+            0 => locale.text("today"),
+            1 => locale.text("yesterday"),
+            days if days < 0 => locale.text_with("in-days", &[("days", (-days).into())]),
+            days => locale.text_with("days-ago", &[("days", days.into())]),
+        });
 
-        if !(year_difference != 0 || month_difference != 0 && month_difference != 1)
-            || (month_difference == -11 && year_difference == 1)
-        {
-            conesecutive_months += 1;
-        } else {
-            self.number_of_consecutive_months = conesecutive_months;
-            return;
+        if ui.button(locale.text("delete")).clicked() {
+            self.pending_delete = self.selected_event(state).cloned();
         }
+    }
 
-        for ((year, month, _day), (previous_year, previous_month, _previous_dayy)) in
-            self.dates.iter().rev().tuple_windows()
-        {
-            let month_difference = *month as i32 - *previous_month as i32;
-            let year_difference = year - previous_year;
-
-            if month_difference == 0 && year_difference == 0 {
-                continue;
-            }
+    /// Asks before deleting an event the list or the detail removed, if the settings say so
+    pub(crate) fn confirm_delete_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        state: &AppState,
+        bus: &mut MessageBus,
+    ) {
+        let locale = state.locale();
+
+        let event = match &self.pending_delete {
+            Some(event) => event.clone(),
+            None => return,
+        };
 
-            if (month_difference == 1 && year_difference == 0)
-                || (month_difference == -11 && year_difference == 1)
-            {
-                conesecutive_months += 1;
-            } else {
-                break;
-            }
+        if !state.settings().confirm_delete {
+            bus.send(Message::EventRemoved(event));
+            self.pending_delete = None;
+            return;
         }
 
-        self.number_of_consecutive_months = conesecutive_months;
-    }
-
-    fn calculate_number_of_events_last_year(&mut self) {
-        self.sort_by_date();
+        let mut is_confirmed = false;
+        let mut is_cancelled = false;
 
-        let mut events_last_year = 0;
+        egui::Window::new(locale.text("delete-event-title"))
+            .id(egui::Id::new("delete_event"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ui.ctx(), |ui| {
+                ui.label(state.format_date(event.date));
 
-        let current_date = Local::now();
-        let current_year = current_date.year();
-        let current_month = current_date.month();
+                ui.horizontal(|ui| {
+                    is_confirmed = ui.button(locale.text("delete")).clicked();
+                    is_cancelled = ui.button(locale.text("cancel")).clicked();
+                });
+            });
 
-        for (year, month, _day) in self.dates.iter().rev() {
-            let month_difference = current_month as i32 - *month as i32;
-            let year_difference = current_year - year;
+        is_cancelled |= ui.input_mut(|i| i.consume_key(Modifiers::NONE, Key::Escape));
 
-            if year_difference == 0 || (year_difference == 1 && month_difference < 0) {
-                events_last_year += 1;
-            } else {
-                break;
-            }
+        if is_confirmed {
+            bus.send(Message::EventRemoved(event));
         }
 
-        self.number_of_events_last_year = events_last_year;
+        if is_confirmed || is_cancelled {
+            self.pending_delete = None;
+        }
     }
 
-    fn sort_by_date(&mut self) {
-        let mut dates = self.dates.clone();
-        dates.sort_by(|(a_year, a_month, a_day), (b_year, b_month, b_day)| {
-            match a_year.cmp(b_year) {
-                std::cmp::Ordering::Less => return std::cmp::Ordering::Less,
-                std::cmp::Ordering::Greater => return std::cmp::Ordering::Greater,
-                std::cmp::Ordering::Equal => (),
-            };
-
-            match a_month.cmp(b_month) {
-                std::cmp::Ordering::Less => return std::cmp::Ordering::Less,
-                std::cmp::Ordering::Greater => return std::cmp::Ordering::Greater,
-                std::cmp::Ordering::Equal => (),
-            };
-
-            match a_day.cmp(b_day) {
-                std::cmp::Ordering::Less => return std::cmp::Ordering::Less,
-                std::cmp::Ordering::Greater => return std::cmp::Ordering::Greater,
-                std::cmp::Ordering::Equal => (),
-            };
-
-            std::cmp::Ordering::Equal
-        });
+    /// The date picker and the button to add the picked date
+    pub(crate) fn picker_ui(&mut self, ui: &mut egui::Ui, state: &AppState, bus: &mut MessageBus) {
+        let settings = state.settings();
+        let today = state.today();
+
+        let first_year = today.year() - settings.picker_years as i32;
+        let mut date = self.picked.unwrap_or(today);
+
+        ui.add(
+            DatePicker::new("event_date", &mut date)
+                .min(NaiveDate::from_ymd_opt(first_year, 1, 1).unwrap())
+                .max(NaiveDate::from_ymd_opt(today.year(), 12, 31).unwrap())
+                .week_start(settings.week_start)
+                .locale(state.locale())
+                .text_style(TextStyle::Name("DateInputButton".into())),
+        );
+
+        self.picked = Some(date);
+
+        let add = ui.button("+");
+        set_accessible_name(
+            &add,
+            state
+                .locale()
+                .text_with("add-event", &[("date", state.format_date(date).into())]),
+        );
+
+        if add.clicked() {
+            let tracker = &settings.default_tracker;
+            bus.send(Message::EventAdded(Event::of_tracker(date, tracker)));
+        }
+    }
+}
 
-        self.dates = dates;
+impl Dates {
+    /// Closes the delete confirmation without deleting
+    pub fn cancel_delete(&mut self) {
+        self.pending_delete = None;
     }
 }
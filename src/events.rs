@@ -0,0 +1,315 @@
+use std::{collections::BTreeMap, convert::TryFrom, iter::FromIterator, ops::RangeBounds};
+
+use chrono::{Days, NaiveDate};
+
+use crate::constants::DEFAULT_TRACKER;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Event {
+    pub date: NaiveDate,
+    /// What the event was logged for, like the trackers of the reminder rules
+    pub tracker: String,
+}
+
+impl Event {
+    /// An event of the tracker the app starts with
+    pub fn new(date: NaiveDate) -> Self {
+        Self::of_tracker(date, DEFAULT_TRACKER)
+    }
+
+    pub fn of_tracker(date: NaiveDate, tracker: &str) -> Self {
+        Self {
+            date,
+            tracker: tracker.to_owned(),
+        }
+    }
+}
+
+/// Events kept sorted by their date
+#[derive(Default)]
+pub struct Events {
+    events: BTreeMap<NaiveDate, Vec<Event>>,
+    /// The number of events on every day, to find the `index`th event without walking all days
+    /// before it
+    day_counts: DayCounts,
+    /// How many events every tracker has on each of its dates
+    tracker_dates: BTreeMap<String, BTreeMap<NaiveDate, usize>>,
+    len: usize,
+}
+
+impl FromIterator<Event> for Events {
+    fn from_iter<I: IntoIterator<Item = Event>>(iter: I) -> Self {
+        let mut events = Self::default();
+
+        for event in iter {
+            events.insert(event);
+        }
+
+        events
+    }
+}
+
+impl Events {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn insert(&mut self, event: Event) {
+        let date = event.date;
+        *self
+            .tracker_dates
+            .entry(event.tracker.clone())
+            .or_default()
+            .entry(date)
+            .or_default() += 1;
+
+        self.day_counts.cover(date, &self.events);
+        self.events.entry(date).or_default().push(event);
+        self.day_counts.update(date, |count| count + 1);
+
+        self.len += 1;
+    }
+
+    /// Removes the most recently added event equal to `event`
+    pub fn remove(&mut self, event: &Event) -> Option<Event> {
+        let date = event.date;
+        let events_on_date = self.events.get_mut(&date)?;
+        let index = events_on_date.iter().rposition(|other| other == event)?;
+        let event = events_on_date.remove(index);
+
+        if events_on_date.is_empty() {
+            self.events.remove(&date);
+        }
+        self.day_counts.update(date, |count| count - 1);
+        self.forget_tracker_date(&event);
+
+        self.len -= 1;
+
+        Some(event)
+    }
+
+    fn forget_tracker_date(&mut self, event: &Event) {
+        let dates = match self.tracker_dates.get_mut(&event.tracker) {
+            Some(dates) => dates,
+            None => return,
+        };
+
+        if let Some(count) = dates.get_mut(&event.date) {
+            *count -= 1;
+            if *count == 0 {
+                dates.remove(&event.date);
+            }
+        }
+
+        if dates.is_empty() {
+            self.tracker_dates.remove(&event.tracker);
+        }
+    }
+
+    /// The most recently added event on `date`
+    pub fn last_on(&self, date: NaiveDate) -> Option<&Event> {
+        self.events.get(&date)?.last()
+    }
+
+    /// All events, oldest first
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Event> {
+        self.events.values().flatten()
+    }
+
+    /// All events starting at the `index`th one, the day of that event is found in O(log days)
+    pub fn iter_from(&self, index: usize) -> impl Iterator<Item = &Event> {
+        let day = self.day_counts.find(index).filter(|_| index < self.len);
+
+        let (first_day, later_days) = match day {
+            Some((date, start)) => (
+                &self.events[&date][index - start..],
+                Some(self.events.range(date..).skip(1)),
+            ),
+            None => (&[][..], None),
+        };
+
+        first_day.iter().chain(
+            later_days
+                .into_iter()
+                .flatten()
+                .flat_map(|(_date, events)| events),
+        )
+    }
+
+    /// The events with a date inside `range`, oldest first
+    /// The index of the first event on or after `date`
+    pub fn index_of(&self, date: NaiveDate) -> usize {
+        self.day_counts.before(date)
+    }
+
+    pub fn range<R: RangeBounds<NaiveDate>>(
+        &self,
+        range: R,
+    ) -> impl DoubleEndedIterator<Item = &Event> {
+        self.events.range(range).flat_map(|(_date, events)| events)
+    }
+
+    /// Number of events with a date inside `range`
+    pub fn count<R: RangeBounds<NaiveDate>>(&self, range: R) -> usize {
+        self.events
+            .range(range)
+            .map(|(_date, events)| events.len())
+            .sum()
+    }
+
+    /// The latest date that has an event and lies inside `range`
+    pub fn last_date_in<R: RangeBounds<NaiveDate>>(&self, range: R) -> Option<NaiveDate> {
+        self.events
+            .range(range)
+            .next_back()
+            .map(|(date, _events)| *date)
+    }
+
+    /// The trackers that have events, sorted
+    pub fn trackers(&self) -> impl Iterator<Item = &str> {
+        self.tracker_dates.keys().map(String::as_str)
+    }
+
+    /// Number of events of `tracker` with a date inside `range`
+    pub fn count_of<R: RangeBounds<NaiveDate>>(&self, tracker: &str, range: R) -> usize {
+        self.tracker_dates.get(tracker).map_or(0, |dates| {
+            dates.range(range).map(|(_date, count)| count).sum()
+        })
+    }
+
+    /// The latest date that has an event of `tracker` and lies inside `range`
+    pub fn last_date_of<R: RangeBounds<NaiveDate>>(
+        &self,
+        tracker: &str,
+        range: R,
+    ) -> Option<NaiveDate> {
+        self.tracker_dates
+            .get(tracker)?
+            .range(range)
+            .next_back()
+            .map(|(date, _count)| *date)
+    }
+}
+
+/// The number of events on every day from `first_day` on, as a Fenwick tree. Updating a day and
+/// finding the day of the `index`th event take O(log days).
+#[derive(Default)]
+struct DayCounts {
+    first_day: Option<NaiveDate>,
+    /// 1-based, `tree[i]` holds the sum of the `i & -i` days up to day `i - 1`
+    tree: Vec<usize>,
+}
+
+impl DayCounts {
+    fn days(&self) -> usize {
+        self.tree.len().saturating_sub(1)
+    }
+
+    fn offset(&self, date: NaiveDate) -> Option<usize> {
+        let days = (date - self.first_day?).num_days();
+        usize::try_from(days)
+            .ok()
+            .filter(|days| *days < self.days())
+    }
+
+    /// Makes room for `date`, rebuilt from `events` with at least twice the days so that
+    /// growing stays rare
+    fn cover(&mut self, date: NaiveDate, events: &BTreeMap<NaiveDate, Vec<Event>>) {
+        if self.offset(date).is_some() {
+            return;
+        }
+
+        let (mut first_day, last_day) = match self.first_day {
+            Some(first_day) => (
+                first_day.min(date),
+                (first_day + Days::new(self.days() as u64 - 1)).max(date),
+            ),
+            None => (date, date),
+        };
+
+        let needed = (last_day - first_day).num_days() as usize + 1;
+        let days = needed.max(2 * self.days());
+
+        // Days before the covered ones grow it into the past, later days into the future
+        if date < last_day {
+            first_day = last_day
+                .checked_sub_days(Days::new(days as u64 - 1))
+                .unwrap_or(first_day);
+        }
+
+        let mut tree = vec![0; days + 1];
+        for (day, events) in events {
+            if let Ok(offset) = usize::try_from((*day - first_day).num_days()) {
+                tree[offset + 1] = events.len();
+            }
+        }
+
+        for i in 1..tree.len() {
+            let parent = i + (i & i.wrapping_neg());
+            if parent < tree.len() {
+                tree[parent] += tree[i];
+            }
+        }
+
+        self.first_day = Some(first_day);
+        self.tree = tree;
+    }
+
+    /// Changes the count of `date`, which has to be covered
+    fn update(&mut self, date: NaiveDate, update: impl Fn(usize) -> usize) {
+        let mut i = match self.offset(date) {
+            Some(offset) => offset + 1,
+            None => return,
+        };
+
+        while i < self.tree.len() {
+            self.tree[i] = update(self.tree[i]);
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// The number of events before `date`
+    fn before(&self, date: NaiveDate) -> usize {
+        let first_day = match self.first_day {
+            Some(first_day) if first_day < date => first_day,
+            _ => return 0,
+        };
+
+        let mut i = usize::try_from((date - first_day).num_days())
+            .unwrap_or(usize::MAX)
+            .min(self.days());
+        let mut count = 0;
+
+        while i > 0 {
+            count += self.tree[i];
+            i -= i & i.wrapping_neg();
+        }
+
+        count
+    }
+
+    /// The day of the `index`th event and the number of events before that day
+    fn find(&self, index: usize) -> Option<(NaiveDate, usize)> {
+        let first_day = self.first_day?;
+        let days = self.days();
+
+        // Walks down the tree to the last day with at most `index` events up to it
+        let mut day = 0;
+        let mut start = 0;
+        let mut step = (days + 1).next_power_of_two() / 2;
+
+        while step > 0 {
+            if day + step <= days && start + self.tree[day + step] <= index {
+                day += step;
+                start += self.tree[day];
+            }
+            step /= 2;
+        }
+
+        (day < days).then(|| (first_day + Days::new(day as u64), start))
+    }
+}
@@ -0,0 +1,229 @@
+use std::ops::Range;
+
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+use egui::{ComboBox, DragValue};
+
+use crate::{
+    events::Events,
+    helper::{set_accessible_name, StateView},
+    i18n::Locale,
+    state::{AppState, Message, MessageBus},
+};
+
+/// How many completed periods are shown in the history of a goal
+const HISTORY_LENGTH: usize = 6;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GoalPeriod {
+    Week,
+    Month,
+    Year,
+    RollingYear,
+}
+
+impl GoalPeriod {
+    pub const ALL: [GoalPeriod; 4] = [Self::Week, Self::Month, Self::Year, Self::RollingYear];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Week => "week",
+            Self::Month => "month",
+            Self::Year => "year",
+            Self::RollingYear => "rolling year",
+        }
+    }
+
+    /// The name in the language of `locale`
+    pub fn text(&self, locale: &Locale) -> String {
+        locale.text(&format!("period-{}", self.key()))
+    }
+
+    /// The key used in the storage file
+    pub(crate) fn key(&self) -> &'static str {
+        match self {
+            Self::Week => "week",
+            Self::Month => "month",
+            Self::Year => "year",
+            Self::RollingYear => "rolling_year",
+        }
+    }
+
+    pub(crate) fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|period| period.key() == key)
+    }
+
+    /// The period that contains `date`
+    pub fn containing(&self, date: NaiveDate, week_start: Weekday) -> Range<NaiveDate> {
+        match self {
+            Self::Week => {
+                let start = date.week(week_start).first_day();
+                start..start + Days::new(7)
+            }
+            Self::Month => {
+                let start = date.with_day(1).unwrap();
+                start..start + Months::new(1)
+            }
+            Self::Year => {
+                let start = NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap();
+                start..start + Months::new(12)
+            }
+            Self::RollingYear => {
+                let end = date + Days::new(1);
+                end - Months::new(12)..end
+            }
+        }
+    }
+
+    fn previous(&self, period: &Range<NaiveDate>) -> Range<NaiveDate> {
+        let length = match self {
+            Self::Week => return period.start - Days::new(7)..period.start,
+            Self::Month => Months::new(1),
+            Self::Year | Self::RollingYear => Months::new(12),
+        };
+
+        period.start - length..period.start
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Goal {
+    /// The minimum number of events per period
+    pub target: usize,
+    pub period: GoalPeriod,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PeriodResult {
+    pub period: Range<NaiveDate>,
+    pub count: usize,
+    pub is_met: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GoalStatus {
+    pub goal: Goal,
+    /// Events in the current period so far
+    pub count: usize,
+    /// Events expected at the end of the current period at the current pace
+    pub projected: usize,
+    /// The completed periods before the current one, most recent first
+    pub history: Vec<PeriodResult>,
+}
+
+impl Goal {
+    /// E.g. "3 per week"
+    pub fn text(&self, locale: &Locale) -> String {
+        locale.text_with(
+            "goal",
+            &[
+                ("target", self.target.into()),
+                ("period", self.period.text(locale).into()),
+            ],
+        )
+    }
+
+    pub fn status(&self, events: &Events, today: NaiveDate, week_start: Weekday) -> GoalStatus {
+        let period = self.period.containing(today, week_start);
+        let count = events.count(period.clone());
+
+        let projected = match self.period {
+            // A rolling period always covers its full length
+            GoalPeriod::RollingYear => count,
+            GoalPeriod::Week | GoalPeriod::Month | GoalPeriod::Year => {
+                let elapsed_days = (today - period.start).num_days() + 1;
+                let total_days = (period.end - period.start).num_days();
+
+                (count as f32 * total_days as f32 / elapsed_days as f32).round() as usize
+            }
+        };
+
+        let mut history = Vec::with_capacity(HISTORY_LENGTH);
+        let mut previous = self.period.previous(&period);
+
+        for _ in 0..HISTORY_LENGTH {
+            let count = events.count(previous.clone());
+
+            history.push(PeriodResult {
+                period: previous.clone(),
+                count,
+                is_met: count >= self.target,
+            });
+
+            previous = self.period.previous(&previous);
+        }
+
+        GoalStatus {
+            goal: *self,
+            count,
+            projected,
+            history,
+        }
+    }
+}
+
+/// The list of goals and the inputs to add one
+pub struct Goals {
+    new_goal: Goal,
+}
+
+impl Default for Goals {
+    fn default() -> Self {
+        Self {
+            new_goal: Goal {
+                target: 1,
+                period: GoalPeriod::Month,
+            },
+        }
+    }
+}
+
+impl StateView for Goals {
+    fn ui(&mut self, ui: &mut egui::Ui, state: &AppState, bus: &mut MessageBus) {
+        let locale = state.locale();
+
+        ui.collapsing(locale.text("goals"), |ui| {
+            for (index, goal) in state.goals().iter().enumerate() {
+                ui.horizontal(|ui| {
+                    let text = goal.text(locale);
+                    ui.label(&text);
+
+                    let delete = ui.button("x");
+                    set_accessible_name(
+                        &delete,
+                        locale.text_with("delete-goal", &[("goal", text.into())]),
+                    );
+
+                    delete.clicked().then(|| {
+                        bus.send(Message::GoalRemoved(index));
+                    });
+                });
+            }
+
+            ui.horizontal(|ui| {
+                let target =
+                    ui.add(DragValue::new(&mut self.new_goal.target).clamp_range(1..=1000));
+                set_accessible_name(&target, locale.text("goal-target"));
+
+                let period = ComboBox::from_id_source("goal_period")
+                    .selected_text(self.new_goal.period.text(locale))
+                    .show_ui(ui, |ui| {
+                        for period in GoalPeriod::ALL {
+                            ui.selectable_value(
+                                &mut self.new_goal.period,
+                                period,
+                                period.text(locale),
+                            );
+                        }
+                    });
+                set_accessible_name(&period.response, locale.text("goal-period"));
+
+                let add = ui.button("+");
+                set_accessible_name(&add, locale.text("add-goal"));
+
+                add.clicked().then(|| {
+                    bus.send(Message::GoalAdded(self.new_goal));
+                });
+            });
+        });
+    }
+}
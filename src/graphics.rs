@@ -0,0 +1,310 @@
+use std::env;
+
+/// The sample counts offered in the settings
+pub const MSAA_SAMPLES: [u32; 4] = [1, 2, 4, 8];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PresentMode {
+    /// Waits for the display, supported everywhere
+    Vsync,
+    /// Replaces a waiting frame instead of blocking
+    Mailbox,
+    /// No waiting, may tear
+    Immediate,
+}
+
+impl PresentMode {
+    pub const ALL: [PresentMode; 3] = [Self::Vsync, Self::Mailbox, Self::Immediate];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Vsync => "Vsync",
+            Self::Mailbox => "Mailbox",
+            Self::Immediate => "Immediate",
+        }
+    }
+
+    /// The key used in the storage file and environment
+    pub(crate) fn key(&self) -> &'static str {
+        match self {
+            Self::Vsync => "vsync",
+            Self::Mailbox => "mailbox",
+            Self::Immediate => "immediate",
+        }
+    }
+
+    pub(crate) fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|mode| mode.key() == key)
+    }
+}
+
+impl From<PresentMode> for wgpu::PresentMode {
+    fn from(mode: PresentMode) -> Self {
+        match mode {
+            PresentMode::Vsync => wgpu::PresentMode::Fifo,
+            PresentMode::Mailbox => wgpu::PresentMode::Mailbox,
+            PresentMode::Immediate => wgpu::PresentMode::Immediate,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PowerPreference {
+    LowPower,
+    HighPerformance,
+}
+
+impl PowerPreference {
+    pub const ALL: [PowerPreference; 2] = [Self::LowPower, Self::HighPerformance];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::LowPower => "Low power",
+            Self::HighPerformance => "High performance",
+        }
+    }
+
+    /// The key used in the storage file and environment, as in wgpu's `WGPU_POWER_PREF`
+    pub(crate) fn key(&self) -> &'static str {
+        match self {
+            Self::LowPower => "low",
+            Self::HighPerformance => "high",
+        }
+    }
+
+    pub(crate) fn from_key(key: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|preference| preference.key() == key)
+    }
+}
+
+impl From<PowerPreference> for wgpu::PowerPreference {
+    fn from(preference: PowerPreference) -> Self {
+        match preference {
+            PowerPreference::LowPower => wgpu::PowerPreference::LowPower,
+            PowerPreference::HighPerformance => wgpu::PowerPreference::HighPerformance,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    /// Whatever wgpu picks
+    Auto,
+    Vulkan,
+    Gl,
+}
+
+impl Backend {
+    pub const ALL: [Backend; 3] = [Self::Auto, Self::Vulkan, Self::Gl];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Auto => "Automatic",
+            Self::Vulkan => "Vulkan",
+            Self::Gl => "OpenGL",
+        }
+    }
+
+    /// The key used in the storage file and environment, as in wgpu's `WGPU_BACKEND`
+    pub(crate) fn key(&self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Vulkan => "vulkan",
+            Self::Gl => "gl",
+        }
+    }
+
+    pub(crate) fn from_key(key: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|backend| backend.key() == key)
+    }
+
+    pub fn backends(&self) -> wgpu::Backends {
+        match self {
+            Self::Auto => wgpu::Backends::PRIMARY | wgpu::Backends::GL,
+            Self::Vulkan => wgpu::Backends::VULKAN,
+            Self::Gl => wgpu::Backends::GL,
+        }
+    }
+}
+
+/// How the GPU is set up, changes are applied after a restart
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GraphicsSettings {
+    pub msaa_samples: u32,
+    pub present_mode: PresentMode,
+    pub power_preference: PowerPreference,
+    pub backend: Backend,
+}
+
+impl Default for GraphicsSettings {
+    fn default() -> Self {
+        Self {
+            msaa_samples: 1,
+            present_mode: PresentMode::Vsync,
+            power_preference: PowerPreference::LowPower,
+            backend: Backend::Auto,
+        }
+    }
+}
+
+/// What an adapter can do with the window's surface
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GraphicsCapabilities {
+    pub sample_counts: Vec<u32>,
+    pub present_modes: Vec<wgpu::PresentMode>,
+}
+
+impl GraphicsSettings {
+    /// Replaces settings with `WGPU_MSAA`, `WGPU_PRESENT_MODE`, `WGPU_POWER_PREF` and
+    /// `WGPU_BACKEND` from the environment
+    pub fn with_env_overrides(mut self) -> Self {
+        if let Some(msaa_samples) = env_value("WGPU_MSAA", |value| value.parse().ok()) {
+            self.msaa_samples = msaa_samples;
+        }
+
+        if let Some(present_mode) = env_value("WGPU_PRESENT_MODE", PresentMode::from_key) {
+            self.present_mode = present_mode;
+        }
+
+        if let Some(power_preference) = env_value("WGPU_POWER_PREF", PowerPreference::from_key) {
+            self.power_preference = power_preference;
+        }
+
+        if let Some(backend) = env_value("WGPU_BACKEND", Backend::from_key) {
+            self.backend = backend;
+        }
+
+        self
+    }
+
+    /// The settings to try when these get no surface, `None` if they already use OpenGL
+    pub fn fallback(&self) -> Option<Self> {
+        if self.backend == Backend::Gl {
+            return None;
+        }
+
+        Some(Self {
+            backend: Backend::Gl,
+            ..*self
+        })
+    }
+
+    /// Replaces what `capabilities` doesn't support with the closest supported setting
+    pub fn supported_by(mut self, capabilities: &GraphicsCapabilities) -> Self {
+        if !capabilities.sample_counts.contains(&self.msaa_samples) {
+            let fallback = capabilities
+                .sample_counts
+                .iter()
+                .copied()
+                .filter(|samples| *samples < self.msaa_samples)
+                .max()
+                .unwrap_or(1);
+
+            log::warn!(
+                "{}x MSAA is not supported, using {}x",
+                self.msaa_samples,
+                fallback
+            );
+            self.msaa_samples = fallback;
+        }
+
+        if !capabilities
+            .present_modes
+            .contains(&self.present_mode.into())
+        {
+            log::warn!(
+                "Present mode {} is not supported, using vsync",
+                self.present_mode.key()
+            );
+            self.present_mode = PresentMode::Vsync;
+        }
+
+        self
+    }
+}
+
+fn env_value<T>(name: &str, parse: impl FnOnce(&str) -> Option<T>) -> Option<T> {
+    let value = env::var(name).ok()?;
+    let parsed = parse(&value.to_lowercase());
+
+    if parsed.is_none() {
+        log::warn!("Ignoring {}={}", name, value);
+    }
+
+    parsed
+}
+
+/// `settings` with what the GPU doesn't support for `window` replaced, see `supported_by`.
+///
+/// A backend without an adapter for the window falls back to `Backend::Auto`.
+pub(crate) fn supported_settings(
+    mut settings: GraphicsSettings,
+    window: &winit::window::Window,
+) -> GraphicsSettings {
+    let capabilities = match capabilities(settings, window) {
+        Some(capabilities) => capabilities,
+        None if settings.backend != Backend::Auto => {
+            log::warn!(
+                "No {} adapter for the window, using any backend",
+                settings.backend.name()
+            );
+            settings.backend = Backend::Auto;
+
+            match capabilities(settings, window) {
+                Some(capabilities) => capabilities,
+                None => return settings.supported_by(&minimal_capabilities()),
+            }
+        }
+        // Creating the surface will fail later on and be retried
+        None => return settings.supported_by(&minimal_capabilities()),
+    };
+
+    settings.supported_by(&capabilities)
+}
+
+/// What every adapter supports
+fn minimal_capabilities() -> GraphicsCapabilities {
+    GraphicsCapabilities {
+        sample_counts: vec![1],
+        present_modes: vec![wgpu::PresentMode::Fifo],
+    }
+}
+
+/// Asks the adapter egui-wgpu would pick for `window` what it supports
+fn capabilities(
+    settings: GraphicsSettings,
+    window: &winit::window::Window,
+) -> Option<GraphicsCapabilities> {
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+        backends: settings.backend.backends(),
+        ..Default::default()
+    });
+
+    // SAFETY: the surface is dropped at the end of the function, before the window
+    let surface = unsafe { instance.create_surface(window) }.ok()?;
+
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: settings.power_preference.into(),
+        force_fallback_adapter: false,
+        compatible_surface: Some(&surface),
+    }))?;
+
+    let surface_capabilities = surface.get_capabilities(&adapter);
+    let format = egui_wgpu::preferred_framebuffer_format(&surface_capabilities.formats).ok()?;
+    let features = adapter.get_texture_format_features(format).flags;
+
+    Some(GraphicsCapabilities {
+        sample_counts: MSAA_SAMPLES
+            .iter()
+            .copied()
+            .filter(|samples| features.sample_count_supported(*samples))
+            .collect(),
+        present_modes: surface_capabilities.present_modes,
+    })
+}
@@ -0,0 +1,290 @@
+use std::{collections::HashMap, error::Error, fs, io::BufWriter, path::Path};
+
+use egui::{
+    epaint::{ImageData, Primitive, Vertex},
+    ClippedPrimitive, Color32, ColorImage, Context, Pos2, RawInput, Rect, TextureId, TexturesDelta,
+    Vec2,
+};
+
+/// Frames to run at most before taking the screenshot, layouts like windows and combo boxes
+/// only settle after a few
+const MAX_FRAMES: usize = 8;
+
+/// Rasterizes egui's tessellated meshes on the CPU, so screens can be rendered without a GPU
+/// or a window
+#[derive(Default)]
+pub struct SoftwareRenderer {
+    textures: HashMap<TextureId, ColorImage>,
+}
+
+impl SoftwareRenderer {
+    pub fn update_textures(&mut self, textures_delta: &TexturesDelta) {
+        for (id, delta) in &textures_delta.set {
+            let image = match &delta.image {
+                ImageData::Color(image) => image.clone(),
+                ImageData::Font(image) => ColorImage {
+                    size: image.size,
+                    pixels: image.srgba_pixels(None).collect(),
+                },
+            };
+
+            match delta.pos {
+                Some([x, y]) => {
+                    let texture = self.textures.get_mut(id).unwrap();
+
+                    for row in 0..image.height() {
+                        let start = (y + row) * texture.width() + x;
+                        texture.pixels[start..start + image.width()].copy_from_slice(
+                            &image.pixels[row * image.width()..(row + 1) * image.width()],
+                        );
+                    }
+                }
+                None => {
+                    self.textures.insert(*id, image);
+                }
+            }
+        }
+
+        for id in &textures_delta.free {
+            self.textures.remove(id);
+        }
+    }
+
+    pub fn render(
+        &self,
+        primitives: &[ClippedPrimitive],
+        size_in_pixels: [usize; 2],
+        pixels_per_point: f32,
+        clear_color: Color32,
+    ) -> ColorImage {
+        let mut target = ColorImage::new(size_in_pixels, clear_color);
+
+        for primitive in primitives {
+            let mesh = match &primitive.primitive {
+                Primitive::Mesh(mesh) => mesh,
+                Primitive::Callback(_) => {
+                    log::warn!("Paint callbacks are not supported by the software renderer");
+                    continue;
+                }
+            };
+
+            let texture = match self.textures.get(&mesh.texture_id) {
+                Some(texture) => texture,
+                None => continue,
+            };
+
+            let clip_rect = Rect::from_min_max(
+                (primitive.clip_rect.min.to_vec2() * pixels_per_point).to_pos2(),
+                (primitive.clip_rect.max.to_vec2() * pixels_per_point).to_pos2(),
+            );
+
+            for triangle in mesh.indices.chunks_exact(3) {
+                let vertices = [0, 1, 2].map(|i| {
+                    let vertex = mesh.vertices[triangle[i] as usize];
+
+                    Vertex {
+                        pos: (vertex.pos.to_vec2() * pixels_per_point).to_pos2(),
+                        ..vertex
+                    }
+                });
+
+                fill_triangle(&mut target, clip_rect, vertices, texture);
+            }
+        }
+
+        target
+    }
+}
+
+/// Runs `ui` on a screen of `logical_size` points and renders the last frame
+pub fn screenshot(
+    ctx: &Context,
+    logical_size: Vec2,
+    pixels_per_point: f32,
+    mut ui: impl FnMut(&Context),
+) -> ColorImage {
+    let mut renderer = SoftwareRenderer::default();
+    let mut primitives = Vec::new();
+
+    for _ in 0..MAX_FRAMES {
+        let raw_input = RawInput {
+            screen_rect: Some(Rect::from_min_size(Pos2::ZERO, logical_size)),
+            pixels_per_point: Some(pixels_per_point),
+            ..Default::default()
+        };
+
+        let full_output = ctx.run(raw_input, &mut ui);
+
+        renderer.update_textures(&full_output.textures_delta);
+        primitives = ctx.tessellate(full_output.shapes);
+
+        if !full_output.repaint_after.is_zero() {
+            break;
+        }
+    }
+
+    let size_in_pixels = (logical_size * pixels_per_point).round();
+
+    renderer.render(
+        &primitives,
+        [size_in_pixels.x as usize, size_in_pixels.y as usize],
+        pixels_per_point,
+        ctx.style().visuals.window_fill(),
+    )
+}
+
+pub fn save_png(image: &ColorImage, path: &Path) -> Result<(), Box<dyn Error>> {
+    let file = BufWriter::new(fs::File::create(path)?);
+
+    let mut encoder = png::Encoder::new(file, image.width() as u32, image.height() as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let data: Vec<u8> = image
+        .pixels
+        .iter()
+        .flat_map(|pixel| pixel.to_srgba_unmultiplied())
+        .collect();
+
+    encoder.write_header()?.write_image_data(&data)?;
+
+    Ok(())
+}
+
+pub fn load_png(path: &Path) -> Result<ColorImage, Box<dyn Error>> {
+    let decoder = png::Decoder::new(fs::File::open(path)?);
+    let mut reader = decoder.read_info()?;
+
+    let mut data = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut data)?;
+
+    if info.color_type != png::ColorType::Rgba || info.bit_depth != png::BitDepth::Eight {
+        return Err(format!("{} is not an 8 bit RGBA image", path.display()).into());
+    }
+
+    Ok(ColorImage::from_rgba_unmultiplied(
+        [info.width as usize, info.height as usize],
+        &data[..info.buffer_size()],
+    ))
+}
+
+/// Twice the signed area of the triangle `a`, `b`, `p`
+fn edge(a: Pos2, b: Pos2, p: Pos2) -> f32 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+/// Fills the pixels whose centers lie inside the triangle, pixels on an edge shared by two
+/// triangles are only filled by one of them, following the top-left rule of GPUs
+fn fill_triangle(
+    target: &mut ColorImage,
+    clip_rect: Rect,
+    [a, mut b, mut c]: [Vertex; 3],
+    texture: &ColorImage,
+) {
+    let mut area = edge(a.pos, b.pos, c.pos);
+
+    if area == 0.0 {
+        return;
+    }
+
+    if area < 0.0 {
+        std::mem::swap(&mut b, &mut c);
+        area = -area;
+    }
+
+    let bounds = Rect::from_points(&[a.pos, b.pos, c.pos])
+        .intersect(clip_rect)
+        .intersect(Rect::from_min_size(
+            Pos2::ZERO,
+            Vec2::new(target.width() as f32, target.height() as f32),
+        ));
+
+    if !bounds.is_positive() {
+        return;
+    }
+
+    let is_top_left = |from: Pos2, to: Pos2| {
+        let delta = to - from;
+        (delta.y == 0.0 && delta.x > 0.0) || delta.y < 0.0
+    };
+    let edges = [
+        (b.pos, c.pos, is_top_left(b.pos, c.pos)),
+        (c.pos, a.pos, is_top_left(c.pos, a.pos)),
+        (a.pos, b.pos, is_top_left(a.pos, b.pos)),
+    ];
+
+    for y in bounds.min.y.floor() as usize..bounds.max.y.ceil() as usize {
+        for x in bounds.min.x.floor() as usize..bounds.max.x.ceil() as usize {
+            let p = Pos2::new(x as f32 + 0.5, y as f32 + 0.5);
+
+            if !bounds.contains(p) {
+                continue;
+            }
+
+            let mut weights = [0.0; 3];
+            let mut is_inside = true;
+
+            for (weight, (from, to, is_top_left)) in weights.iter_mut().zip(edges) {
+                let e = edge(from, to, p);
+                is_inside &= e > 0.0 || (e == 0.0 && is_top_left);
+                *weight = e / area;
+            }
+
+            if !is_inside {
+                continue;
+            }
+
+            let [wa, wb, wc] = weights;
+            let uv = a.uv.to_vec2() * wa + b.uv.to_vec2() * wb + c.uv.to_vec2() * wc;
+            let color = |channel: usize| {
+                a.color[channel] as f32 * wa
+                    + b.color[channel] as f32 * wb
+                    + c.color[channel] as f32 * wc
+            };
+
+            let texel = sample(texture, uv.to_pos2());
+            let source = [0, 1, 2, 3].map(|channel| color(channel) * texel[channel] / 255.0);
+
+            let width = target.width();
+            let pixel = &mut target.pixels[y * width + x];
+            *pixel = blend(source, *pixel);
+        }
+    }
+}
+
+/// Bilinear sample with clamped edges, `uv` is normalized
+fn sample(texture: &ColorImage, uv: Pos2) -> [f32; 4] {
+    let x = (uv.x * texture.width() as f32 - 0.5).clamp(0.0, (texture.width() - 1) as f32);
+    let y = (uv.y * texture.height() as f32 - 0.5).clamp(0.0, (texture.height() - 1) as f32);
+
+    let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+    let (x1, y1) = (
+        (x0 + 1).min(texture.width() - 1),
+        (y0 + 1).min(texture.height() - 1),
+    );
+    let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+
+    let texel = |x: usize, y: usize, channel: usize| {
+        texture.pixels[y * texture.width() + x][channel] as f32
+    };
+
+    [0, 1, 2, 3].map(|channel| {
+        let top = texel(x0, y0, channel) * (1.0 - fx) + texel(x1, y0, channel) * fx;
+        let bottom = texel(x0, y1, channel) * (1.0 - fx) + texel(x1, y1, channel) * fx;
+        top * (1.0 - fy) + bottom * fy
+    })
+}
+
+/// Premultiplied alpha "over"
+fn blend(source: [f32; 4], destination: Color32) -> Color32 {
+    let coverage = 1.0 - source[3] / 255.0;
+    let [r, g, b, a] =
+        [0, 1, 2, 3].map(|channel| source[channel] + destination[channel] as f32 * coverage);
+
+    Color32::from_rgba_premultiplied(
+        r.round().clamp(0.0, 255.0) as u8,
+        g.round().clamp(0.0, 255.0) as u8,
+        b.round().clamp(0.0, 255.0) as u8,
+        a.round().clamp(0.0, 255.0) as u8,
+    )
+}
@@ -1,3 +1,5 @@
+use crate::state::{AppState, MessageBus};
+
 /// Something to view in the demo windows
 pub trait View {
     fn ui(&mut self, ui: &mut egui::Ui);
@@ -12,6 +14,37 @@ pub trait Demo {
     fn show(&mut self, ctx: &egui::Context, open: &mut bool);
 }
 
-pub fn is_mobile() -> bool {
-    cfg!(target_os = "android")
+/// Something to view of the `AppState`, changes are sent to the bus instead of made in place
+pub trait StateView {
+    fn ui(&mut self, ui: &mut egui::Ui, state: &AppState, bus: &mut MessageBus);
+}
+
+/// A screen in the navigation, see `MainWindows::register`
+pub trait Screen {
+    /// `&'static` so we can also use it as a key in the navigation.
+    fn name(&self) -> &'static str;
+
+    /// Shown with the name in the navigation
+    fn icon(&self) -> &'static str;
+
+    /// The mobile layout, a single pane
+    fn ui(&mut self, ui: &mut egui::Ui, state: &AppState, bus: &mut MessageBus);
+
+    /// The desktop layout, next to the sidebar, may split the screen into several panes
+    fn desktop_ui(&mut self, ui: &mut egui::Ui, state: &AppState, bus: &mut MessageBus) {
+        self.ui(ui, state, bus);
+    }
+
+    /// Called before the first frame after the screen was opened
+    fn on_enter(&mut self, _state: &AppState) {}
+
+    /// Called before the first frame after another screen was opened
+    fn on_leave(&mut self, _state: &AppState) {}
+}
+
+/// Names a widget for screen readers, for widgets whose visible text alone says too little
+pub(crate) fn set_accessible_name(response: &egui::Response, name: impl Into<String>) {
+    response
+        .ctx
+        .accesskit_node_builder(response.id, |builder| builder.set_name(name.into()));
 }
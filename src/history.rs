@@ -0,0 +1,54 @@
+use crate::state::Message;
+
+/// How many changes can be undone
+const MAX_UNDO: usize = 100;
+
+/// The changes to the events that can be undone and redone, newest last
+#[derive(Default)]
+pub(crate) struct History {
+    undo: Vec<Message>,
+    redo: Vec<Message>,
+}
+
+impl History {
+    /// Remembers a change the user made, the undone changes can't be redone after that
+    pub fn record(&mut self, message: &Message) {
+        if inverse(message).is_none() {
+            return;
+        }
+
+        if self.undo.len() == MAX_UNDO {
+            self.undo.remove(0);
+        }
+
+        self.undo.push(message.clone());
+        self.redo.clear();
+    }
+
+    /// The message that reverts the last change
+    pub fn undo(&mut self) -> Option<Message> {
+        let message = self.undo.pop()?;
+        let inverse = inverse(&message);
+
+        self.redo.push(message);
+
+        inverse
+    }
+
+    /// The message that makes the last undone change again
+    pub fn redo(&mut self) -> Option<Message> {
+        let message = self.redo.pop()?;
+
+        self.undo.push(message.clone());
+
+        Some(message)
+    }
+}
+
+fn inverse(message: &Message) -> Option<Message> {
+    match message {
+        Message::EventAdded(event) => Some(Message::EventRemoved(event.clone())),
+        Message::EventRemoved(event) => Some(Message::EventAdded(event.clone())),
+        _ => None,
+    }
+}
@@ -1,35 +1,163 @@
-use egui::RichText;
+use std::time::Duration;
 
-use crate::helper::View;
+use egui::{Button, ProgressBar, RichText, TextStyle};
 
+use crate::{
+    events::Event,
+    goals::GoalStatus,
+    helper::{set_accessible_name, StateView},
+    state::{AppState, Message, MessageBus},
+};
+
+/// Taps on "Log today" closer together than this count once, in seconds
+const DOUBLE_TAP_GUARD: f64 = 1.0;
+/// How long the undo toast stays, in seconds
+const TOAST_DURATION: f64 = 5.0;
+
+/// "Log today", the statistics and the progress of the goals
 #[derive(Default)]
-#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
-#[cfg_attr(feature = "serde", serde(default))]
 pub struct Home {
-    pub number_of_consecutive_months: usize,
-    pub number_of_events_last_year: usize,
+    /// The event and input time of the last "Log today"
+    last_logged: Option<(Event, f64)>,
 }
 
-impl View for Home {
-    fn ui(&mut self, ui: &mut egui::Ui) {
+impl StateView for Home {
+    fn ui(&mut self, ui: &mut egui::Ui, state: &AppState, bus: &mut MessageBus) {
+        self.log_today_ui(ui, state, bus);
+
+        let statistics = state.statistics();
+        let locale = state.locale();
+
         ui.vertical(|ui| {
-            let consecutive_months_text = RichText::new(format!(
-                "Consecutive Months: {}",
-                self.number_of_consecutive_months
+            let consecutive_months_text = RichText::new(locale.text_with(
+                "home-consecutive-months",
+                &[("count", statistics.consecutive_months.into())],
             ))
-            .size(40.0);
+            .text_style(TextStyle::Name("Statistic".into()));
 
             ui.label(consecutive_months_text);
 
             // ui.separator();
 
-            let events_last_year_text = RichText::new(format!(
-                "Events Last Year: {}",
-                self.number_of_events_last_year
+            let events_last_year_text = RichText::new(locale.text_with(
+                "home-recent-events",
+                &[
+                    ("months", state.settings().statistic_months.into()),
+                    ("count", statistics.recent_events.into()),
+                ],
             ))
-            .size(40.0);
+            .text_style(TextStyle::Name("Statistic".into()));
 
             ui.label(events_last_year_text);
+
+            let week_events_text = RichText::new(locale.text_with(
+                "home-week-events",
+                &[("count", statistics.week_events.into())],
+            ))
+            .text_style(TextStyle::Name("Statistic".into()));
+
+            ui.label(week_events_text);
+
+            for status in &statistics.goals {
+                goal_ui(ui, state, status);
+            }
         });
     }
 }
+
+impl Home {
+    fn log_today_ui(&mut self, ui: &mut egui::Ui, state: &AppState, bus: &mut MessageBus) {
+        let now = ui.input(|i| i.time);
+        let today = state.today();
+        let locale = state.locale();
+
+        let text = RichText::new(format!("✚ {}", locale.text("log-today")))
+            .text_style(TextStyle::Name("Statistic".into()));
+        let log_today = ui
+            .add_sized([ui.available_width(), 48.0], Button::new(text))
+            .on_hover_text(locale.text_with(
+                "log-today-hint",
+                &[("tracker", state.settings().default_tracker.as_str().into())],
+            ));
+        set_accessible_name(&log_today, locale.text("log-today"));
+
+        let is_double_tap =
+            matches!(self.last_logged, Some((_, logged_at)) if now - logged_at < DOUBLE_TAP_GUARD);
+
+        if log_today.clicked() && !is_double_tap {
+            let event = Event::of_tracker(today, &state.settings().default_tracker);
+            bus.send(Message::EventAdded(event.clone()));
+            self.last_logged = Some((event, now));
+        }
+
+        self.undo_toast_ui(ui.ctx(), state, bus, now);
+    }
+
+    /// Offers to undo the last "Log today" for a few seconds
+    fn undo_toast_ui(
+        &mut self,
+        ctx: &egui::Context,
+        state: &AppState,
+        bus: &mut MessageBus,
+        now: f64,
+    ) {
+        let (event, logged_at) = match &self.last_logged {
+            Some((event, logged_at)) if now - logged_at < TOAST_DURATION => {
+                (event.clone(), *logged_at)
+            }
+            _ => return,
+        };
+
+        egui::Area::new("log_today_toast")
+            .order(egui::Order::Foreground)
+            // Above the bottom bar of the mobile layout
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -72.0))
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(state.locale().text_with(
+                            "logged",
+                            &[("date", state.format_date(event.date).into())],
+                        ));
+
+                        if ui.button(state.locale().text("undo")).clicked() {
+                            bus.send(Message::EventRemoved(event.clone()));
+                            self.last_logged = None;
+                        }
+                    });
+                });
+            });
+
+        // Hides the toast when its time is over
+        ctx.request_repaint_after(Duration::from_secs_f64(logged_at + TOAST_DURATION - now));
+    }
+}
+
+fn goal_ui(ui: &mut egui::Ui, state: &AppState, status: &GoalStatus) {
+    let goal = status.goal;
+    let locale = state.locale();
+
+    ui.separator();
+
+    ui.label(goal.text(locale));
+
+    let progress = (status.count as f32 / goal.target as f32).min(1.0);
+    ui.add(ProgressBar::new(progress).text(format!("{} / {}", status.count, goal.target)));
+
+    ui.label(locale.text_with("goal-projected", &[("count", status.projected.into())]));
+
+    ui.horizontal_wrapped(|ui| {
+        for result in status.history.iter().rev() {
+            let symbol = if result.is_met { "✔" } else { "✖" };
+
+            ui.label(symbol).on_hover_text(locale.text_with(
+                "goal-result",
+                &[
+                    ("start", state.format_date(result.period.start).into()),
+                    ("count", result.count.into()),
+                    ("target", goal.target.into()),
+                ],
+            ));
+        }
+    });
+}
@@ -0,0 +1,209 @@
+use std::sync::OnceLock;
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource, FluentValue};
+use unic_langid::LanguageIdentifier;
+
+/// The languages with a message catalog in `i18n/`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Language {
+    English,
+    German,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Self::English, Self::German];
+
+    /// The name in the language itself, so it can be found without understanding the current one
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::English => "English",
+            Self::German => "Deutsch",
+        }
+    }
+
+    /// The language subtag, also the key used in the storage file
+    pub(crate) fn key(&self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::German => "de",
+        }
+    }
+
+    pub(crate) fn from_key(key: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|language| language.key() == key)
+    }
+
+    /// The language of a locale like "de_DE.UTF-8" from `LANG` or "de-AT" from Android,
+    /// `None` for languages without a catalog
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        let language = locale.split(['_', '-', '.', '@']).next()?;
+
+        Self::from_key(&language.to_ascii_lowercase())
+    }
+
+    fn catalog(&self) -> &'static str {
+        match self {
+            Self::English => include_str!("../i18n/en.ftl"),
+            Self::German => include_str!("../i18n/de.ftl"),
+        }
+    }
+}
+
+/// The messages, month and weekday names and date formats of a language.
+///
+/// Messages missing from the catalog of the language are taken from the English one.
+pub struct Locale {
+    language: Language,
+    bundle: FluentBundle<FluentResource>,
+}
+
+impl Locale {
+    pub fn new(language: Language) -> Self {
+        let resource = FluentResource::try_new(language.catalog().to_owned()).unwrap_or_else(
+            |(resource, errors)| {
+                log::error!("Errors in the {} catalog: {:?}", language.key(), errors);
+                resource
+            },
+        );
+
+        let id: LanguageIdentifier = language.key().parse().unwrap();
+        let mut bundle = FluentBundle::new_concurrent(vec![id]);
+        // egui has no bidirectional text, the isolation marks would show up as boxes
+        bundle.set_use_isolating(false);
+
+        if let Err(errors) = bundle.add_resource(resource) {
+            log::error!("Errors in the {} catalog: {:?}", language.key(), errors);
+        }
+
+        Self { language, bundle }
+    }
+
+    /// Shared, as every other locale falls back to it
+    pub fn english() -> &'static Locale {
+        static ENGLISH: OnceLock<Locale> = OnceLock::new();
+
+        ENGLISH.get_or_init(|| Locale::new(Language::English))
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    /// The message `id`, or `id` itself if no catalog has it
+    pub fn text(&self, id: &str) -> String {
+        self.text_with(id, &[])
+    }
+
+    /// The message `id` with the placeables filled from `args`
+    pub fn text_with(&self, id: &str, args: &[(&str, FluentValue)]) -> String {
+        self.try_text_with(id, args).unwrap_or_else(|| {
+            log::warn!("No message {} in any catalog", id);
+            id.to_owned()
+        })
+    }
+
+    /// The message `id`, `None` if no catalog has it
+    pub fn try_text(&self, id: &str) -> Option<String> {
+        self.try_text_with(id, &[])
+    }
+
+    fn try_text_with(&self, id: &str, args: &[(&str, FluentValue)]) -> Option<String> {
+        self.message(id, args).or_else(|| match self.language {
+            Language::English => None,
+            _ => Self::english().message(id, args),
+        })
+    }
+
+    fn message(&self, id: &str, args: &[(&str, FluentValue)]) -> Option<String> {
+        let pattern = self.bundle.get_message(id)?.value()?;
+
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(*name, value.clone());
+        }
+
+        let mut errors = Vec::new();
+        let text = self
+            .bundle
+            .format_pattern(pattern, Some(&fluent_args), &mut errors);
+
+        if !errors.is_empty() {
+            log::warn!("Errors in message {}: {:?}", id, errors);
+        }
+
+        Some(text.into_owned())
+    }
+
+    /// The full name of `month`, 1 is January
+    pub fn month_name(&self, month: u32) -> String {
+        self.text(&format!("month-{}", month))
+    }
+
+    /// The abbreviated name of `month`, 1 is January
+    pub fn short_month_name(&self, month: u32) -> String {
+        self.text(&format!("month-short-{}", month))
+    }
+
+    pub fn weekday_name(&self, weekday: Weekday) -> String {
+        self.text(&format!("weekday-{}", weekday_key(weekday)))
+    }
+
+    /// Two letters or so, for the columns of a calendar
+    pub fn short_weekday_name(&self, weekday: Weekday) -> String {
+        self.text(&format!("weekday-short-{}", weekday_key(weekday)))
+    }
+
+    /// The date the way the language writes it, with the abbreviated month name
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        self.text_with(
+            "date",
+            &date_args(date, self.short_month_name(date.month())),
+        )
+    }
+
+    /// The date with the weekday and the full month name, e.g. for screen readers
+    pub fn format_long_date(&self, date: NaiveDate) -> String {
+        let mut args = date_args(date, self.month_name(date.month()));
+        args.push(("weekday", self.weekday_name(date.weekday()).into()));
+
+        self.text_with("date-long", &args)
+    }
+
+    /// A month of a year, e.g. above a calendar
+    pub fn format_month(&self, date: NaiveDate) -> String {
+        self.text_with(
+            "month-of-year",
+            &date_args(date, self.short_month_name(date.month())),
+        )
+    }
+
+    /// The numeric date pattern of the language for `chrono`, e.g. "%m/%d/%Y"
+    pub(crate) fn date_pattern(&self) -> String {
+        self.text("date-pattern")
+    }
+}
+
+/// The placeables of the date messages, `month` is a name
+fn date_args(date: NaiveDate, month: String) -> Vec<(&'static str, FluentValue<'static>)> {
+    vec![
+        ("day", date.day().into()),
+        ("month", month.into()),
+        ("year", date.year().into()),
+    ]
+}
+
+fn weekday_key(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "mon",
+        Weekday::Tue => "tue",
+        Weekday::Wed => "wed",
+        Weekday::Thu => "thu",
+        Weekday::Fri => "fri",
+        Weekday::Sat => "sat",
+        Weekday::Sun => "sun",
+    }
+}
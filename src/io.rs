@@ -1,44 +1,439 @@
 use std::{
-    fs,
-    io::{BufRead, BufReader, Write},
-    path::Path,
+    collections::{BTreeMap, HashSet},
+    convert::TryFrom,
+    io,
 };
 
-use crate::constants::STORAGE_PATH;
+use chrono::{Datelike, NaiveDate, Weekday};
+use egui::Color32;
 
-pub(crate) fn write_to_file(dates: Vec<(i32, u32, u32)>) {
-    if !Path::new(STORAGE_PATH).exists() {
-        fs::create_dir(STORAGE_PATH).unwrap();
-    }
+use crate::{
+    constants::DEFAULT_TRACKER,
+    events::Event,
+    goals::{Goal, GoalPeriod},
+    graphics::{Backend, PowerPreference, PresentMode},
+    i18n::Language,
+    layout::FormFactor,
+    reminders::{Reminder, ReminderKind, ReminderRules},
+    settings::{DateFormat, Settings, Theme},
+    storage::Storage,
+    window::WindowGeometry,
+};
 
-    let mut file = fs::File::create(format!("{}/dates.dat", STORAGE_PATH)).unwrap();
+/// One event per line, the tracker is left out for the default one, as in files from before
+/// there were trackers
+pub(crate) fn write_to_file<'a>(
+    storage: &dyn Storage,
+    events: impl Iterator<Item = &'a Event>,
+) -> io::Result<()> {
+    let mut file = String::new();
 
-    for (year, month, day) in dates {
-        let date = format!("{} {} {}\n", year, month, day);
-        file.write_all(date.as_bytes()).unwrap();
-    }
-}
+    for event in events {
+        let date = event.date;
+        file += &format!("{} {} {}", date.year(), date.month(), date.day());
 
-pub(crate) fn read_from_file() -> Vec<(i32, u32, u32)> {
-    let mut dates = Vec::new();
+        if event.tracker != DEFAULT_TRACKER {
+            file += &format!(" {}", event.tracker);
+        }
 
-    if !Path::new(&format!("{}/dates.dat", STORAGE_PATH)).exists() {
-        return dates;
+        file += "\n";
     }
 
-    let file = fs::File::open(format!("{}/dates.dat", STORAGE_PATH)).unwrap();
-    let reader = BufReader::new(file);
+    storage.write("dates.dat", &file)
+}
+
+pub(crate) fn read_from_file(storage: &dyn Storage) -> Vec<Event> {
+    let mut events = Vec::new();
 
-    for line in reader.lines() {
-        let line = line.unwrap();
+    let file = match storage.read("dates.dat") {
+        Some(file) => file,
+        None => return events,
+    };
+
+    for line in file.lines() {
         let mut split = line.split_whitespace();
 
         let year = split.next().unwrap().parse::<i32>().unwrap();
         let month = split.next().unwrap().parse::<u32>().unwrap();
         let day = split.next().unwrap().parse::<u32>().unwrap();
+        let tracker = split.next().unwrap_or(DEFAULT_TRACKER);
+
+        match NaiveDate::from_ymd_opt(year, month, day) {
+            Some(date) => events.push(Event::of_tracker(date, tracker)),
+            None => log::warn!("Skipping invalid date {} {} {}", year, month, day),
+        }
+    }
+
+    events
+}
+
+pub(crate) fn write_reminder_rules(
+    storage: &dyn Storage,
+    rules: &BTreeMap<String, ReminderRules>,
+) -> io::Result<()> {
+    let mut file = String::new();
+
+    for (tracker, rules) in rules {
+        if let Some(days) = rules.streak_at_risk_days {
+            let kind = ReminderKind::StreakAtRisk.key();
+            file += &format!("{} {} {}\n", tracker, kind, days);
+        }
+
+        if let Some(days) = rules.recurring_days {
+            let kind = ReminderKind::Recurring.key();
+            file += &format!("{} {} {}\n", tracker, kind, days);
+        }
+    }
+
+    storage.write("reminders.dat", &file)
+}
+
+pub(crate) fn read_reminder_rules(storage: &dyn Storage) -> BTreeMap<String, ReminderRules> {
+    let mut rules = BTreeMap::<String, ReminderRules>::new();
+
+    let file = match storage.read("reminders.dat") {
+        Some(file) => file,
+        None => return rules,
+    };
+
+    for line in file.lines() {
+        let split: Vec<&str> = line.split_whitespace().collect();
+
+        let rule = match split[..] {
+            [tracker, kind, days] => ReminderKind::from_key(kind)
+                .zip(days.parse::<u32>().ok())
+                .map(|(kind, days)| (tracker, kind, days)),
+            _ => None,
+        };
+
+        let (tracker, kind, days) = match rule {
+            Some(rule) => rule,
+            None => {
+                log::warn!("Skipping invalid reminder rule {}", line);
+                continue;
+            }
+        };
+
+        let tracker_rules = rules.entry(tracker.to_owned()).or_default();
+
+        match kind {
+            ReminderKind::StreakAtRisk => tracker_rules.streak_at_risk_days = Some(days),
+            ReminderKind::Recurring => tracker_rules.recurring_days = Some(days),
+        }
+    }
+
+    rules
+}
+
+pub(crate) fn write_shown_reminders(
+    storage: &dyn Storage,
+    reminders: &HashSet<Reminder>,
+) -> io::Result<()> {
+    let mut file = String::new();
+
+    for reminder in reminders {
+        file += &format!(
+            "{} {} {} {} {}\n",
+            reminder.tracker,
+            reminder.kind.key(),
+            reminder.due.year(),
+            reminder.due.month(),
+            reminder.due.day()
+        );
+    }
+
+    storage.write("shown_reminders.dat", &file)
+}
+
+pub(crate) fn read_shown_reminders(storage: &dyn Storage) -> HashSet<Reminder> {
+    let mut reminders = HashSet::new();
+
+    let file = match storage.read("shown_reminders.dat") {
+        Some(file) => file,
+        None => return reminders,
+    };
+
+    for line in file.lines() {
+        let split: Vec<&str> = line.split_whitespace().collect();
+
+        let reminder = match split[..] {
+            [tracker, kind, year, month, day] => ReminderKind::from_key(kind).and_then(|kind| {
+                let due = NaiveDate::from_ymd_opt(
+                    year.parse().ok()?,
+                    month.parse().ok()?,
+                    day.parse().ok()?,
+                )?;
+
+                Some(Reminder {
+                    tracker: tracker.to_owned(),
+                    kind,
+                    due,
+                })
+            }),
+            _ => None,
+        };
+
+        match reminder {
+            Some(reminder) => {
+                reminders.insert(reminder);
+            }
+            None => log::warn!("Skipping invalid shown reminder {}", line),
+        }
+    }
+
+    reminders
+}
+
+pub(crate) fn write_goals(storage: &dyn Storage, goals: &[Goal]) -> io::Result<()> {
+    let mut file = String::new();
+
+    for goal in goals {
+        file += &format!("{} {}\n", goal.target, goal.period.key());
+    }
+
+    storage.write("goals.dat", &file)
+}
+
+pub(crate) fn read_goals(storage: &dyn Storage) -> Vec<Goal> {
+    let mut goals = Vec::new();
+
+    let file = match storage.read("goals.dat") {
+        Some(file) => file,
+        None => return goals,
+    };
+
+    for line in file.lines() {
+        let mut split = line.split_whitespace();
+
+        let target = split.next().and_then(|target| target.parse::<usize>().ok());
+        let period = split.next().and_then(GoalPeriod::from_key);
+
+        match (target, period) {
+            (Some(target), Some(period)) => goals.push(Goal { target, period }),
+            _ => log::warn!("Skipping invalid goal {}", line),
+        }
+    }
+
+    goals
+}
+
+pub(crate) fn write_settings(storage: &dyn Storage, settings: &Settings) -> io::Result<()> {
+    let theme = match settings.theme {
+        Theme::System => "system",
+        Theme::Light => "light",
+        Theme::Dark => "dark",
+    };
+
+    let date_format = match settings.date_format {
+        DateFormat::Automatic => "auto",
+        DateFormat::YearMonthDay => "ymd",
+        DateFormat::DayMonthYear => "dmy",
+        DateFormat::MonthDayYear => "mdy",
+    };
+
+    let lines = [
+        format!("theme {}\n", theme),
+        format!(
+            "accent_color {:02x}{:02x}{:02x}\n",
+            settings.accent_color.r(),
+            settings.accent_color.g(),
+            settings.accent_color.b()
+        ),
+        format!("text_scale {}\n", settings.text_scale),
+        format!(
+            "language {}\n",
+            settings
+                .language
+                .map_or("system", |language| language.key())
+        ),
+        format!(
+            "week_start {}\n",
+            settings.week_start.num_days_from_monday()
+        ),
+        format!("date_format {}\n", date_format),
+        format!("picker_years {}\n", settings.picker_years),
+        format!("statistic_months {}\n", settings.statistic_months),
+        format!("confirm_delete {}\n", settings.confirm_delete),
+        format!(
+            "layout {}\n",
+            settings
+                .form_factor
+                .map_or("auto", |form_factor| form_factor.key())
+        ),
+        format!("msaa {}\n", settings.graphics.msaa_samples),
+        format!("present_mode {}\n", settings.graphics.present_mode.key()),
+        format!(
+            "power_preference {}\n",
+            settings.graphics.power_preference.key()
+        ),
+        format!("backend {}\n", settings.graphics.backend.key()),
+        format!("default_tracker {}\n", settings.default_tracker),
+    ];
+
+    storage.write("settings.dat", &lines.concat())
+}
+
+pub(crate) fn read_settings(storage: &dyn Storage) -> Settings {
+    let mut settings = Settings::default();
+
+    let file = match storage.read("settings.dat") {
+        Some(file) => file,
+        None => return settings,
+    };
+
+    for line in file.lines() {
+        let mut split = line.split_whitespace();
+
+        let (key, value) = match (split.next(), split.next()) {
+            (Some(key), Some(value)) => (key, value),
+            _ => {
+                log::warn!("Skipping invalid setting {}", line);
+                continue;
+            }
+        };
+
+        // `None` for values that can't be read, the setting keeps its default then
+        let is_valid = match key {
+            "theme" => {
+                let theme = match value {
+                    "system" => Some(Theme::System),
+                    "light" => Some(Theme::Light),
+                    "dark" => Some(Theme::Dark),
+                    _ => None,
+                };
+                theme.map(|theme| settings.theme = theme)
+            }
+            "accent_color" => u32::from_str_radix(value, 16).ok().map(|rgb| {
+                settings.accent_color =
+                    Color32::from_rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)
+            }),
+            "text_scale" => value
+                .parse()
+                .ok()
+                .map(|text_scale| settings.text_scale = text_scale),
+            "language" => match value {
+                "system" => Some(None),
+                _ => Language::from_key(value).map(Some),
+            }
+            .map(|language| settings.language = language),
+            "week_start" => value
+                .parse::<u8>()
+                .ok()
+                .and_then(|day| Weekday::try_from(day).ok())
+                .map(|week_start| settings.week_start = week_start),
+            "date_format" => {
+                let date_format = match value {
+                    "auto" => Some(DateFormat::Automatic),
+                    "ymd" => Some(DateFormat::YearMonthDay),
+                    "dmy" => Some(DateFormat::DayMonthYear),
+                    "mdy" => Some(DateFormat::MonthDayYear),
+                    _ => None,
+                };
+                date_format.map(|date_format| settings.date_format = date_format)
+            }
+            "picker_years" => value
+                .parse()
+                .ok()
+                .map(|years| settings.picker_years = years),
+            "statistic_months" => value
+                .parse()
+                .ok()
+                .map(|months| settings.statistic_months = months),
+            "confirm_delete" => value
+                .parse()
+                .ok()
+                .map(|confirm_delete| settings.confirm_delete = confirm_delete),
+            "layout" => {
+                // Anything else picks the layout from the screen size
+                settings.form_factor = FormFactor::from_key(value);
+                Some(())
+            }
+            "msaa" => value
+                .parse()
+                .ok()
+                .map(|samples| settings.graphics.msaa_samples = samples),
+            "present_mode" => PresentMode::from_key(value)
+                .map(|present_mode| settings.graphics.present_mode = present_mode),
+            "power_preference" => PowerPreference::from_key(value)
+                .map(|preference| settings.graphics.power_preference = preference),
+            "backend" => {
+                Backend::from_key(value).map(|backend| settings.graphics.backend = backend)
+            }
+            "default_tracker" => {
+                settings.default_tracker = value.to_owned();
+                Some(())
+            }
+            _ => {
+                log::warn!("Skipping unknown setting {}", key);
+                continue;
+            }
+        }
+        .is_some();
+
+        if !is_valid {
+            log::warn!("Skipping invalid value of setting {}", line);
+        }
+    }
+
+    settings
+}
+
+pub(crate) fn write_window_geometry(
+    storage: &dyn Storage,
+    geometry: &WindowGeometry,
+) -> io::Result<()> {
+    let mut file = String::new();
+
+    if let Some([x, y]) = geometry.position {
+        file += &format!("position {} {}\n", x, y);
+    }
+
+    file += &format!("size {} {}\n", geometry.size[0], geometry.size[1]);
+    file += &format!("maximized {}\n", geometry.maximized);
+
+    storage.write("window.dat", &file)
+}
+
+pub(crate) fn read_window_geometry(storage: &dyn Storage) -> Option<WindowGeometry> {
+    let file = storage.read("window.dat")?;
+
+    let mut geometry = WindowGeometry {
+        position: None,
+        size: [0, 0],
+        maximized: false,
+    };
+
+    for line in file.lines() {
+        let split: Vec<&str> = line.split_whitespace().collect();
+
+        let is_valid = match split[..] {
+            ["position", x, y] => x
+                .parse()
+                .ok()
+                .zip(y.parse().ok())
+                .map(|(x, y)| geometry.position = Some([x, y])),
+            ["size", width, height] => width
+                .parse()
+                .ok()
+                .zip(height.parse().ok())
+                .map(|(width, height)| geometry.size = [width, height]),
+            ["maximized", maximized] => maximized
+                .parse()
+                .ok()
+                .map(|maximized| geometry.maximized = maximized),
+            _ => None,
+        }
+        .is_some();
+
+        if !is_valid {
+            log::warn!("Skipping invalid window setting {}", line);
+        }
+    }
 
-        dates.push((year, month, day));
+    // The window opens with the default geometry without a size
+    if geometry.size == [0, 0] {
+        return None;
     }
 
-    dates
+    Some(geometry)
 }
@@ -0,0 +1,135 @@
+use egui::Vec2;
+
+/// Window size classes, picked from the logical window width
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// Phones in portrait
+    Compact,
+    /// Phones in landscape, small tablets and narrow desktop windows
+    Medium,
+    /// Tablets and desktop windows
+    Expanded,
+}
+
+impl Breakpoint {
+    pub fn from_logical_size(logical_size: Vec2) -> Self {
+        if logical_size.x < 600.0 {
+            Self::Compact
+        } else if logical_size.x < 840.0 {
+            Self::Medium
+        } else {
+            Self::Expanded
+        }
+    }
+}
+
+/// Which of the two layouts to use: a bottom bar and one pane, or a sidebar and several panes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FormFactor {
+    Mobile,
+    Desktop,
+}
+
+impl FormFactor {
+    pub const ALL: [FormFactor; 2] = [Self::Mobile, Self::Desktop];
+
+    /// Narrow windows get the mobile layout, and so do touch screens in portrait that are too
+    /// narrow for the desktop layout to fit comfortably, like tablets and unfolded foldables
+    pub fn detect(logical_size: Vec2, is_touch: bool) -> Self {
+        match Breakpoint::from_logical_size(logical_size) {
+            Breakpoint::Compact => Self::Mobile,
+            Breakpoint::Medium if is_touch && logical_size.y > logical_size.x => Self::Mobile,
+            Breakpoint::Medium | Breakpoint::Expanded => Self::Desktop,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Mobile => "Mobile",
+            Self::Desktop => "Desktop",
+        }
+    }
+
+    /// The key used in the storage file
+    pub(crate) fn key(&self) -> &'static str {
+        match self {
+            Self::Mobile => "mobile",
+            Self::Desktop => "desktop",
+        }
+    }
+
+    pub(crate) fn from_key(key: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|form_factor| form_factor.key() == key)
+    }
+}
+
+/// Font sizes in points
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Typography {
+    pub heading: f32,
+    pub body: f32,
+    pub button: f32,
+    pub small: f32,
+    pub monospace: f32,
+    pub date_input: f32,
+    pub navigation: f32,
+    pub statistic: f32,
+}
+
+impl Typography {
+    /// The font sizes for `breakpoint`, multiplied by the user's `text_scale`
+    pub fn new(breakpoint: Breakpoint, logical_size: Vec2, text_scale: f32) -> Self {
+        let base = match breakpoint {
+            Breakpoint::Compact => Self {
+                heading: 28.0,
+                body: 20.0,
+                button: 18.0,
+                small: 12.0,
+                monospace: 14.0,
+                date_input: 28.0,
+                navigation: 18.0,
+                statistic: 24.0,
+            },
+            Breakpoint::Medium => Self {
+                heading: 30.0,
+                body: 20.0,
+                button: 18.0,
+                small: 12.0,
+                monospace: 14.0,
+                date_input: 26.0,
+                navigation: 18.0,
+                statistic: 26.0,
+            },
+            Breakpoint::Expanded => Self {
+                heading: 28.0,
+                body: 18.0,
+                button: 16.0,
+                small: 11.0,
+                monospace: 13.0,
+                date_input: 24.0,
+                navigation: 16.0,
+                statistic: 24.0,
+            },
+        };
+
+        // Phones range from about 320 to 480 points in width
+        let scale = match breakpoint {
+            Breakpoint::Compact => (logical_size.x / 400.0).clamp(0.8, 1.2),
+            Breakpoint::Medium | Breakpoint::Expanded => 1.0,
+        } * text_scale;
+
+        Self {
+            heading: base.heading * scale,
+            body: base.body * scale,
+            button: base.button * scale,
+            small: base.small * scale,
+            monospace: base.monospace * scale,
+            date_input: base.date_input * scale,
+            navigation: base.navigation * scale,
+            statistic: base.statistic * scale,
+        }
+    }
+}
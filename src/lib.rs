@@ -1,44 +1,174 @@
 mod about;
+mod accessibility;
 mod constants;
+mod date_input;
+mod date_picker;
 mod dates;
+mod events;
+mod goals;
+mod graphics;
+mod headless;
 mod helper;
+mod history;
 mod home;
+mod i18n;
 mod io;
+mod layout;
+mod navigation;
+mod notifications;
+mod options;
+mod persistence;
+mod platform;
+mod profiler;
+mod redraw;
+mod reminders;
+mod screens;
+mod settings;
+mod shortcuts;
+mod state;
+mod storage;
+mod theme;
 mod ui;
+mod window;
 
-use ui::MainWindows;
+pub use accessibility::{VirtualView, VirtualViewAction, VirtualViews};
+pub use date_input::parse_date;
+pub use date_picker::DatePicker;
+pub use dates::Dates;
+pub use events::{Event, Events};
+pub use goals::{Goal, GoalPeriod, GoalStatus, Goals, PeriodResult};
+pub use graphics::{Backend, GraphicsCapabilities, GraphicsSettings, PowerPreference, PresentMode};
+pub use headless::{load_png, save_png, screenshot, SoftwareRenderer};
+pub use helper::{Demo, Screen, StateView, View};
+pub use i18n::{Language, Locale};
+pub use layout::FormFactor;
+use notifications::platform_backend;
+pub use notifications::{MockNotifications, Notification, NotificationBackend};
+pub use options::{Options, USAGE};
+pub use persistence::{Persistence, WriteOutcome};
+pub use profiler::{FrameProfile, FrameTimes, Profiler, TRACE_FILE};
+pub use redraw::{FrameStats, RedrawScheduler};
+pub use reminders::{Reminder, ReminderKind, ReminderRules, Reminders, RemindersView};
+pub use settings::{DateFormat, Settings, Theme};
+pub use state::{AppState, Message, MessageBus, Statistics};
+pub use storage::{FileStorage, MemoryStorage, Storage};
+use theme::SystemTheme;
+pub use ui::MainWindows;
+pub use window::{WindowGeometry, WindowOptions, APP_TITLE, PHONE_SIZE};
 #[cfg(target_os = "android")]
 use winit::platform::android::activity::AndroidApp;
 
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
 use winit::event::Event::*;
-use winit::event_loop::{ControlFlow, EventLoop, EventLoopBuilder, EventLoopWindowTarget};
+use winit::event::StartCause;
+use winit::event_loop::{
+    ControlFlow, EventLoop, EventLoopBuilder, EventLoopProxy, EventLoopWindowTarget,
+};
+use winit::platform::run_return::EventLoopExtRunReturn;
 
 use egui_wgpu::winit::Painter;
+use egui_wgpu::SurfaceErrorAction;
+use egui_winit::accesskit_winit::ActionRequestEvent;
 use egui_winit::State;
 
 /// A custom event type for the winit app.
-enum Event {
-    RequestRedraw,
+enum AppEvent {
+    /// egui wants a frame after the duration, zero for as soon as possible
+    RequestRedraw(Duration),
+    /// A screen reader asks to focus, click, etc. a widget
+    AccessKitActionRequest(ActionRequestEvent),
+    /// The persistence worker finished a write
+    WriteOutcome(WriteOutcome),
 }
 
+impl From<ActionRequestEvent> for AppEvent {
+    fn from(event: ActionRequestEvent) -> Self {
+        Self::AccessKitActionRequest(event)
+    }
+}
+
+/// The activity the app runs in, to query its configuration
+#[cfg(target_os = "android")]
+static ANDROID_APP: std::sync::OnceLock<AndroidApp> = std::sync::OnceLock::new();
+
 /// Enable egui to request redraws via a custom Winit event...
 #[derive(Clone)]
-struct RepaintSignal(std::sync::Arc<std::sync::Mutex<winit::event_loop::EventLoopProxy<Event>>>);
+struct RepaintSignal(std::sync::Arc<std::sync::Mutex<EventLoopProxy<AppEvent>>>);
+
+/// How long the persistence worker waits for more changes before writing
+const WRITE_DEBOUNCE: Duration = Duration::from_millis(500);
 
-fn create_window<T>(
-    event_loop: &EventLoopWindowTarget<T>,
+/// How often creating the GPU surface is tried before giving up
+const SURFACE_ATTEMPTS: u32 = 5;
+const SURFACE_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+fn create_window(
+    event_loop: &EventLoopWindowTarget<AppEvent>,
+    proxy: &EventLoopProxy<AppEvent>,
+    ctx: &egui::Context,
     state: &mut State,
-    painter: &mut Painter,
-) -> winit::window::Window {
-    let window = winit::window::WindowBuilder::new()
+    builder: winit::window::WindowBuilder,
+) -> Result<winit::window::Window, winit::error::OsError> {
+    // The AccessKit adapter has to be created before the window is shown
+    let window = builder
         .with_decorations(true)
-        .with_resizable(true)
         .with_transparent(false)
-        .with_title("Android Rust Example")
-        .build(event_loop)
-        .unwrap();
+        .with_visible(false)
+        .build(event_loop)?;
+
+    // accesskit_winit has no Android adapter, there `AndroidAccessibility` bridges the tree
+    let accesskit_ctx = ctx.clone();
+    state.init_accesskit(&window, proxy.clone(), move || {
+        accesskit_ctx.enable_accesskit();
+        accesskit_ctx.request_repaint();
+        accesskit_ctx.accesskit_placeholder_tree_update()
+    });
+
+    window.set_visible(true);
+
+    let pixels_per_point = window.scale_factor() as f32;
+    state.set_pixels_per_point(pixels_per_point);
+
+    Ok(window)
+}
 
-    pollster::block_on(painter.set_window(Some(&window))).unwrap();
+/// Creates a painter with the parts of `settings` the GPU supports for `window`
+fn create_painter(
+    window: &winit::window::Window,
+    settings: GraphicsSettings,
+    needs_redraw: Arc<AtomicBool>,
+) -> Painter {
+    let settings = graphics::supported_settings(settings, window);
+
+    log::info!("Graphics settings: {:?}", settings);
+
+    Painter::new(
+        egui_wgpu::WgpuConfiguration {
+            supported_backends: settings.backend.backends(),
+            present_mode: settings.present_mode.into(),
+            power_preference: settings.power_preference.into(),
+            on_surface_error: surface_error_handler(needs_redraw),
+            ..Default::default()
+        },
+        settings.msaa_samples,
+        None,
+        false,
+    )
+}
+
+/// Creates the wgpu surface for `window`, returns `false` if that failed
+fn attach_surface(
+    window: &winit::window::Window,
+    state: &mut State,
+    painter: &mut Painter,
+) -> bool {
+    if let Err(err) = pollster::block_on(painter.set_window(Some(window))) {
+        log::error!("Failed to create the GPU surface: {}", err);
+        return false;
+    }
 
     // NB: calling set_window will lazily initialize render state which
     // means we will be able to query the maximum supported texture
@@ -47,122 +177,435 @@ fn create_window<T>(
         state.set_max_texture_side(max_size);
     }
 
-    let pixels_per_point = window.scale_factor() as f32;
-    state.set_pixels_per_point(pixels_per_point);
-
     window.request_redraw();
 
-    window
+    true
 }
 
-fn _main(event_loop: EventLoop<Event>) {
+/// Recreates lost or outdated surfaces, `needs_redraw` is set to draw the skipped frame again
+fn surface_error_handler(
+    needs_redraw: Arc<AtomicBool>,
+) -> Arc<dyn Fn(wgpu::SurfaceError) -> SurfaceErrorAction> {
+    Arc::new(move |err| match err {
+        wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated => {
+            needs_redraw.store(true, Ordering::Relaxed);
+            SurfaceErrorAction::RecreateSurface
+        }
+        err => {
+            log::warn!("Dropped a frame: {}", err);
+            SurfaceErrorAction::SkipFrame
+        }
+    })
+}
+
+/// The appearance the system currently asks for, if the platform tells us
+fn system_theme(window: &winit::window::Window) -> Option<SystemTheme> {
+    #[cfg(target_os = "android")]
+    {
+        use ndk::configuration::UiModeNight;
+
+        let _ = window;
+
+        match ANDROID_APP.get()?.config().ui_mode_night() {
+            UiModeNight::Yes => Some(SystemTheme::Dark),
+            UiModeNight::No => Some(SystemTheme::Light),
+            _ => None,
+        }
+    }
+
+    #[cfg(not(target_os = "android"))]
+    window.theme().map(SystemTheme::from)
+}
+
+/// Writes a trace that is still being recorded, before the app may be killed
+fn save_trace(profiler: &mut Profiler) {
+    if let Err(err) = profiler.stop_recording() {
+        log::error!("Failed to write the trace: {}", err);
+    }
+}
+
+/// Opens the screen named `name`, ignoring the case
+fn open_screen(main_windows: &mut MainWindows, name: &str) {
+    let names = main_windows.screen_names();
+
+    match names
+        .iter()
+        .find(|screen| screen.eq_ignore_ascii_case(name))
+    {
+        Some(screen) => {
+            main_windows.navigate_to(screen);
+        }
+        None => log::error!(
+            "There is no screen {}, the screens are {}",
+            name,
+            names.join(", ")
+        ),
+    }
+}
+
+fn _main(mut event_loop: EventLoop<AppEvent>, options: Options) {
     let ctx = egui::Context::default();
+    let proxy = event_loop.create_proxy();
     let repaint_signal = RepaintSignal(std::sync::Arc::new(std::sync::Mutex::new(
         event_loop.create_proxy(),
     )));
-    ctx.set_request_repaint_callback(move |_| {
+    #[cfg(target_os = "android")]
+    let accessibility_signal = repaint_signal.clone();
+
+    let file_storage = match &options.data_dir {
+        Some(data_dir) => FileStorage::new(data_dir),
+        None => FileStorage::default(),
+    };
+
+    let write_signal = repaint_signal.clone();
+    let persistence = Persistence::new(Arc::new(file_storage), WRITE_DEBOUNCE, move |outcome| {
+        write_signal
+            .0
+            .lock()
+            .unwrap()
+            .send_event(AppEvent::WriteOutcome(outcome))
+            .ok();
+    });
+
+    ctx.set_request_repaint_callback(move |info| {
         repaint_signal
             .0
             .lock()
             .unwrap()
-            .send_event(Event::RequestRedraw)
+            .send_event(AppEvent::RequestRedraw(info.after))
             .ok();
     });
 
+    let needs_redraw = Arc::new(AtomicBool::new(false));
+
     let mut state = State::new(&event_loop);
-    let mut painter = Painter::new(
-        egui_wgpu::WgpuConfiguration::default(),
-        1, // msaa samples
-        None,
-        false,
-    );
+    // Created with the first window, to check the graphics settings against its surface
+    let mut painter: Option<Painter> = None;
     let mut window: Option<winit::window::Window> = None;
+    // Nothing is drawn without a surface, the UI keeps its state until one can be created
+    let mut has_surface = false;
+    let mut failed_surface_attempts = 0;
+    #[cfg(target_os = "android")]
+    let mut accessibility = accessibility::AndroidAccessibility::default();
+    let mut scheduler = RedrawScheduler::default();
+
+    let storage: Arc<dyn Storage> = Arc::new(persistence);
+    let mut profiler = Profiler::new(storage.clone());
+    let window_options = WindowOptions {
+        phone_preview: options.mobile,
+        ..WindowOptions::default()
+    };
+    let geometry_storage = storage.clone();
+    let mut geometry = window_options.initial_geometry(WindowGeometry::load(&*storage));
 
     // let mut egui_demo_windows = egui_demo_lib::DemoWindows::default();
-    let mut main_windows = MainWindows::default();
+    let mut main_windows = MainWindows::load(storage, platform_backend());
+
+    if let Some(language) = platform::system_language() {
+        main_windows.set_system_language(language);
+    }
 
-    event_loop.run(move |event, event_loop, control_flow| match event {
-        Resumed => match window {
-            None => {
-                window = Some(create_window(event_loop, &mut state, &mut painter));
+    if options.mobile {
+        main_windows.force_form_factor(Some(FormFactor::Mobile));
+    }
+
+    if let Some(screen) = &options.screen {
+        open_screen(&mut main_windows, screen);
+    }
+
+    platform::register_shortcuts(&main_windows.state().locale().text("log-today"));
+
+    // Falls back to OpenGL for the rest of the session if no surface can be created
+    let mut graphics = main_windows
+        .state()
+        .settings()
+        .graphics
+        .with_env_overrides();
+
+    // Returning from `run_return`, unlike `run`, lets Android finish the activity
+    event_loop.run_return(move |event, event_loop, control_flow| match event {
+        Resumed => {
+            // The "Log today" shortcut only records the event, without showing the UI
+            if platform::take_log_today_launch() {
+                main_windows.log_today();
+                main_windows.flush();
+                platform::finish_activity();
+                return;
+            }
+
+            if window.is_none() {
+                let geometry = window::on_a_monitor(geometry, event_loop);
+                let builder = window_options.window_builder(geometry);
+
+                match create_window(event_loop, &proxy, &ctx, &mut state, builder) {
+                    Ok(new_window) => {
+                        if let Some(theme) = system_theme(&new_window) {
+                            main_windows.set_system_theme(theme);
+                        }
+
+                        window = Some(new_window);
+                    }
+                    Err(err) => {
+                        log::error!("Failed to create the window: {}", err);
+                        let locale = main_windows.state().locale();
+                        platform::show_error(None, &locale.text("window-error"));
+                        // Only Android resumes the app again, to retry
+                        *control_flow = if cfg!(target_os = "android") {
+                            ControlFlow::Wait
+                        } else {
+                            ControlFlow::Exit
+                        };
+                    }
+                }
             }
-            Some(ref window) => {
-                pollster::block_on(painter.set_window(Some(window))).unwrap();
-                window.request_redraw();
+
+            let window = match window.as_ref() {
+                Some(window) => window,
+                None => return,
+            };
+
+            // TalkBack may have been turned on while the app was in the background
+            #[cfg(target_os = "android")]
+            {
+                let signal = accessibility_signal.clone();
+                let is_enabled = accessibility.attach(move || {
+                    signal
+                        .0
+                        .lock()
+                        .unwrap()
+                        .send_event(AppEvent::RequestRedraw(Duration::ZERO))
+                        .ok();
+                });
+
+                if is_enabled {
+                    ctx.enable_accesskit();
+                }
             }
-        },
+
+            let painter = painter
+                .get_or_insert_with(|| create_painter(window, graphics, needs_redraw.clone()));
+
+            has_surface = attach_surface(window, &mut state, painter);
+
+            if has_surface {
+                scheduler.request_redraw();
+            } else {
+                failed_surface_attempts = 1;
+                *control_flow = ControlFlow::WaitUntil(Instant::now() + SURFACE_RETRY_DELAY);
+            }
+        }
+        NewEvents(StartCause::ResumeTimeReached { .. }) if !has_surface => {
+            if let (Some(window), Some(painter)) = (window.as_ref(), painter.as_mut()) {
+                has_surface = attach_surface(window, &mut state, painter);
+
+                if has_surface {
+                    scheduler.request_redraw();
+                } else if failed_surface_attempts + 1 < SURFACE_ATTEMPTS {
+                    failed_surface_attempts += 1;
+                    *control_flow = ControlFlow::WaitUntil(Instant::now() + SURFACE_RETRY_DELAY);
+                } else if let Some(fallback) = graphics.fallback() {
+                    log::warn!("Falling back to {}", fallback.backend.name());
+                    graphics = fallback;
+                    *painter = create_painter(window, graphics, needs_redraw.clone());
+                    failed_surface_attempts = 0;
+                    *control_flow = ControlFlow::WaitUntil(Instant::now());
+                } else {
+                    // Resuming the app tries again
+                    log::error!("Giving up on the GPU surface until the app is resumed");
+                    let locale = main_windows.state().locale();
+                    platform::show_error(Some(window), &locale.text("gpu-surface-error"));
+                    *control_flow = ControlFlow::Wait;
+                }
+            }
+        }
+        NewEvents(_) => scheduler.on_wakeup(),
+        // The native window is about to be destroyed on Android, so the surface has to go with
+        // it, and the app may be killed in the background without further notice
         Suspended => {
+            save_trace(&mut profiler);
+            main_windows.flush();
+
+            if let Some(painter) = painter.as_mut() {
+                if let Err(err) = pollster::block_on(painter.set_window(None)) {
+                    log::warn!("Failed to release the GPU surface: {}", err);
+                }
+            }
+
+            has_surface = false;
             window = None;
+            // A retry of the surface would find no window
+            failed_surface_attempts = 0;
+            *control_flow = ControlFlow::Wait;
         }
-        RedrawRequested(..) => {
-            if let Some(window) = window.as_ref() {
+        RedrawRequested(..) if has_surface => {
+            if let (Some(window), Some(painter)) = (window.as_ref(), painter.as_mut()) {
+                #[cfg(target_os = "android")]
+                for request in accessibility.take_action_requests() {
+                    state.on_accesskit_action_request(request);
+                }
+
+                let started = Instant::now();
                 let raw_input = state.take_egui_input(window);
 
                 let full_output = ctx.run(raw_input, |ctx| {
                     main_windows.ui(ctx);
+                    profiler.ui(ctx);
                 });
+
+                #[cfg(target_os = "android")]
+                if let Some(update) = &full_output.platform_output.accesskit_update {
+                    accessibility.update(update, state.pixels_per_point());
+                }
+
                 state.handle_platform_output(window, &ctx, full_output.platform_output);
 
+                if main_windows.is_quit_requested() {
+                    *control_flow = ControlFlow::Exit;
+                    return;
+                }
+
+                let ui_done = Instant::now();
+                let primitives = ctx.tessellate(full_output.shapes);
+                let tessellated = Instant::now();
+
                 painter.paint_and_update_textures(
                     state.pixels_per_point(),
                     egui::Rgba::default().to_array(),
-                    &ctx.tessellate(full_output.shapes),
+                    &primitives,
                     &full_output.textures_delta,
                     false,
                 );
 
-                if full_output.repaint_after.is_zero() {
-                    window.request_redraw();
+                let times = FrameTimes {
+                    ui: ui_done - started,
+                    tessellate: tessellated - ui_done,
+                    paint: tessellated.elapsed(),
+                };
+                profiler.frame_finished(started, times, &primitives, &full_output.textures_delta);
+                scheduler.frame_finished(started, full_output.repaint_after);
+
+                if needs_redraw.swap(false, Ordering::Relaxed) {
+                    scheduler.request_redraw();
                 }
             }
         }
-        MainEventsCleared | UserEvent(Event::RequestRedraw) => {
+        UserEvent(AppEvent::AccessKitActionRequest(event)) => {
+            state.on_accesskit_action_request(event.request);
+            scheduler.request_redraw();
+        }
+        UserEvent(AppEvent::WriteOutcome(outcome)) => {
+            main_windows.on_write_outcome(outcome);
+            scheduler.request_redraw();
+        }
+        UserEvent(AppEvent::RequestRedraw(after)) => scheduler.request_redraw_after(after),
+        MainEventsCleared if has_surface => {
             if let Some(window) = window.as_ref() {
-                window.request_redraw();
+                if scheduler.is_due(Instant::now()) {
+                    window.request_redraw();
+                }
+            }
+        }
+        // Sleeps until the next frame is due or an event arrives, the surface retries set their
+        // own wake up time
+        RedrawEventsCleared if has_surface => {
+            if let ControlFlow::ExitWithCode(_) = *control_flow {
+                return;
             }
+
+            *control_flow = scheduler.control_flow();
         }
         WindowEvent { event, .. } => {
             match event {
                 winit::event::WindowEvent::Resized(size) => {
-                    painter.on_window_resized(size.width, size.height);
+                    if let Some(painter) = painter.as_mut() {
+                        painter.on_window_resized(size.width, size.height);
+                    }
+
+                    if let Some(window) = window.as_ref() {
+                        geometry.update(window);
+                    }
+                }
+                winit::event::WindowEvent::Moved(_) => {
+                    if let Some(window) = window.as_ref() {
+                        geometry.update(window);
+                    }
                 }
                 winit::event::WindowEvent::CloseRequested => {
                     *control_flow = ControlFlow::Exit;
                 }
+                winit::event::WindowEvent::KeyboardInput { input, .. }
+                    if platform::is_back_key(&input) =>
+                {
+                    if let Some(window) = window.as_ref() {
+                        if main_windows.back() {
+                            scheduler.request_redraw();
+                        } else {
+                            platform::move_to_background(window);
+                        }
+                    }
+                }
+                winit::event::WindowEvent::ThemeChanged(theme) => {
+                    main_windows.set_system_theme(theme.into());
+                }
+                // Android reports configuration changes, like night mode or the language, as a scale
+                // factor change
+                winit::event::WindowEvent::ScaleFactorChanged { .. } => {
+                    if let Some(theme) = window.as_ref().and_then(system_theme) {
+                        main_windows.set_system_theme(theme);
+                    }
+
+                    if let Some(language) = platform::system_language() {
+                        main_windows.set_system_language(language);
+                    }
+                }
                 _ => {}
             }
 
             let response = state.on_event(&ctx, &event);
             if response.repaint {
-                if let Some(window) = window.as_ref() {
-                    window.request_redraw();
+                scheduler.request_redraw();
+            }
+        }
+        LoopDestroyed => {
+            save_trace(&mut profiler);
+
+            // The phone preview keeps the geometry of the normal window
+            if !cfg!(target_os = "android") && !window_options.phone_preview {
+                if let Err(err) = geometry.save(&*geometry_storage) {
+                    log::error!("Failed to save the window geometry: {}", err);
                 }
             }
+
+            main_windows.flush();
         }
         _ => (),
     });
 }
 
+/// Logs a panic instead of unwinding into the platform glue, so `android_main` returns and the
+/// activity finishes normally
 #[cfg(any(target_os = "ios", target_os = "android"))]
-fn stop_unwind<F: FnOnce() -> T, T>(f: F) -> T {
-    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)) {
-        Ok(t) => t,
-        Err(err) => {
-            eprintln!("attempt to unwind out of `rust` with err: {:?}", err);
-            std::process::abort()
-        }
+fn stop_unwind<F: FnOnce()>(f: F) {
+    if let Err(err) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)) {
+        log::error!("attempt to unwind out of `rust` with err: {:?}", err);
     }
 }
 
 #[cfg(not(target_os = "android"))]
-pub fn main() {
-    env_logger::builder()
+pub fn main(options: Options) {
+    let mut logger = env_logger::builder();
+    logger
         .filter_level(log::LevelFilter::Warn)
-        .parse_default_env()
-        .init();
+        .parse_default_env();
+
+    if let Some(log_level) = options.log_level {
+        logger.filter_level(log_level);
+    }
+
+    logger.init();
 
     let event_loop = EventLoopBuilder::with_user_event().build();
-    _main(event_loop);
+    _main(event_loop, options);
 }
 
 #[allow(dead_code)]
@@ -175,8 +618,10 @@ fn android_main(app: AndroidApp) {
         android_logger::Config::default().with_max_level(log::LevelFilter::Warn),
     );
 
+    ANDROID_APP.set(app.clone()).ok();
+
     let event_loop = EventLoopBuilder::with_user_event()
         .with_android_app(app)
         .build();
-    stop_unwind(|| _main(event_loop));
+    stop_unwind(|| _main(event_loop, Options::default()));
 }
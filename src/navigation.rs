@@ -0,0 +1,35 @@
+/// The screens the user went through, so back can return to them
+pub(crate) struct Navigation {
+    /// Screen names, never empty, starts with the first screen of the navigation
+    stack: Vec<&'static str>,
+}
+
+impl Navigation {
+    pub fn new(first: &'static str) -> Self {
+        Self { stack: vec![first] }
+    }
+
+    pub fn current(&self) -> &'static str {
+        self.stack.last().unwrap()
+    }
+
+    /// Opens `screen`, going back to it instead if it is already on the stack, so switching
+    /// between tabs never makes the stack grow beyond one entry per screen
+    pub fn navigate_to(&mut self, screen: &'static str) {
+        match self.stack.iter().position(|s| *s == screen) {
+            Some(index) => self.stack.truncate(index + 1),
+            None => self.stack.push(screen),
+        }
+    }
+
+    /// Returns to the previous screen, or returns `false` if already on the first one
+    pub fn back(&mut self) -> bool {
+        if self.stack.len() == 1 {
+            return false;
+        }
+
+        self.stack.pop();
+
+        true
+    }
+}
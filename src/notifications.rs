@@ -0,0 +1,483 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    error::Error,
+    rc::Rc,
+    sync::{
+        mpsc::{self, RecvTimeoutError, Sender},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use chrono::{DateTime, Local};
+
+#[cfg(target_os = "android")]
+use crate::platform::{load_class, with_activity};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Notification {
+    /// Notifications with the same id replace each other
+    pub id: i32,
+    pub title: String,
+    pub body: String,
+}
+
+/// Something that can show a notification to the user
+pub trait NotificationBackend {
+    fn show(&mut self, notification: &Notification) -> Result<(), Box<dyn Error>>;
+
+    /// Shows `notification` at `at`, also when the app doesn't draw then, replaces a scheduled
+    /// notification with the same id
+    fn schedule(
+        &mut self,
+        notification: &Notification,
+        at: DateTime<Local>,
+    ) -> Result<(), Box<dyn Error>>;
+
+    /// Takes back the scheduled notification with `id`, if it hasn't been shown yet
+    fn cancel(&mut self, id: i32) -> Result<(), Box<dyn Error>>;
+
+    /// Whether scheduled notifications are shown even if the app has exited by then
+    fn is_persistent(&self) -> bool;
+
+    /// The ids of the scheduled notifications that were shown since the last call
+    fn take_fired(&mut self) -> Vec<i32>;
+}
+
+/// The notification backend of the platform the app is running on
+pub fn platform_backend() -> Box<dyn NotificationBackend> {
+    #[cfg(target_os = "android")]
+    return Box::new(AndroidNotifications);
+
+    #[cfg(all(
+        unix,
+        not(any(target_os = "android", target_os = "macos", target_os = "ios"))
+    ))]
+    return Box::<DbusNotifications>::default();
+
+    #[allow(unreachable_code)]
+    Box::<LogNotifications>::default()
+}
+
+/// Shows scheduled notifications from a thread per notification, as long as the app runs
+#[derive(Default)]
+struct NotificationTimers {
+    /// Dropping the sender wakes the thread up without showing the notification
+    pending: HashMap<i32, Sender<()>>,
+    /// The ids the threads showed, until `take_fired`
+    fired: Arc<Mutex<Vec<i32>>>,
+}
+
+impl NotificationTimers {
+    fn schedule(
+        &mut self,
+        notification: &Notification,
+        at: DateTime<Local>,
+        show: fn(&Notification) -> Result<(), Box<dyn Error>>,
+    ) -> Result<(), Box<dyn Error>> {
+        let delay = (at - Local::now()).to_std().unwrap_or_default();
+        let id = notification.id;
+        let notification = notification.clone();
+        let (sender, receiver) = mpsc::channel();
+        let fired = self.fired.clone();
+
+        thread::Builder::new()
+            .name("notification timer".to_owned())
+            .spawn(move || {
+                if receiver.recv_timeout(delay) == Err(RecvTimeoutError::Timeout) {
+                    match show(&notification) {
+                        Ok(()) => fired.lock().unwrap().push(id),
+                        Err(error) => log::warn!("Failed to show notification: {}", error),
+                    }
+                }
+            })?;
+
+        self.pending.insert(id, sender);
+
+        Ok(())
+    }
+
+    fn cancel(&mut self, id: i32) {
+        self.pending.remove(&id);
+    }
+
+    fn take_fired(&mut self) -> Vec<i32> {
+        let fired = std::mem::take(&mut *self.fired.lock().unwrap());
+
+        for id in &fired {
+            self.pending.remove(id);
+        }
+
+        fired
+    }
+}
+
+/// Posts notifications through the Android `NotificationManager` via JNI
+#[cfg(target_os = "android")]
+pub struct AndroidNotifications;
+
+#[cfg(target_os = "android")]
+impl AndroidNotifications {
+    const CHANNEL_ID: &'static str = "reminders";
+    const IMPORTANCE_DEFAULT: i32 = 3;
+    /// Posts the scheduled notifications, in `android/src`
+    const WORKER_CLASS: &'static str = "com.example.android_rust_experiments.ReminderWorker";
+    const POST_NOTIFICATIONS: &'static str = "android.permission.POST_NOTIFICATIONS";
+    /// Android 13, from which on notifications need the runtime permission
+    const TIRAMISU: i32 = 33;
+
+    /// Asks for the permission to post notifications if it wasn't granted yet, the context is
+    /// the activity
+    fn request_permission(
+        env: &mut jni::JNIEnv,
+        activity: &jni::objects::JObject,
+    ) -> jni::errors::Result<()> {
+        use jni::objects::{JObject, JValue};
+
+        let sdk = env
+            .get_static_field("android/os/Build$VERSION", "SDK_INT", "I")?
+            .i()?;
+        if sdk < Self::TIRAMISU {
+            return Ok(());
+        }
+
+        let permission = env.new_string(Self::POST_NOTIFICATIONS)?;
+        let granted = env
+            .call_method(
+                activity,
+                "checkSelfPermission",
+                "(Ljava/lang/String;)I",
+                &[JValue::Object(&permission)],
+            )?
+            .i()?;
+        if granted == 0 {
+            return Ok(());
+        }
+
+        let permissions = env.new_object_array(1, "java/lang/String", JObject::null())?;
+        env.set_object_array_element(&permissions, 0, &permission)?;
+        env.call_method(
+            activity,
+            "requestPermissions",
+            "([Ljava/lang/String;I)V",
+            &[JValue::Object(&permissions), JValue::Int(0)],
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "android")]
+impl NotificationBackend for AndroidNotifications {
+    fn show(&mut self, notification: &Notification) -> Result<(), Box<dyn Error>> {
+        use jni::objects::{JObject, JValue};
+
+        let android_context = ndk_context::android_context();
+        let vm = unsafe { jni::JavaVM::from_raw(android_context.vm().cast()) }?;
+        let mut env = vm.attach_current_thread()?;
+        let context = unsafe { JObject::from_raw(android_context.context().cast()) };
+
+        Self::request_permission(&mut env, &context)?;
+
+        let service_name = env
+            .get_static_field(
+                "android/content/Context",
+                "NOTIFICATION_SERVICE",
+                "Ljava/lang/String;",
+            )?
+            .l()?;
+        let manager = env
+            .call_method(
+                &context,
+                "getSystemService",
+                "(Ljava/lang/String;)Ljava/lang/Object;",
+                &[JValue::Object(&service_name)],
+            )?
+            .l()?;
+
+        // Creating an existing channel again is a no-op
+        let channel_id = env.new_string(Self::CHANNEL_ID)?;
+        let channel_name = env.new_string("Reminders")?;
+        let channel = env.new_object(
+            "android/app/NotificationChannel",
+            "(Ljava/lang/String;Ljava/lang/CharSequence;I)V",
+            &[
+                JValue::Object(&channel_id),
+                JValue::Object(&channel_name),
+                JValue::Int(Self::IMPORTANCE_DEFAULT),
+            ],
+        )?;
+        env.call_method(
+            &manager,
+            "createNotificationChannel",
+            "(Landroid/app/NotificationChannel;)V",
+            &[JValue::Object(&channel)],
+        )?;
+
+        let builder = env.new_object(
+            "android/app/Notification$Builder",
+            "(Landroid/content/Context;Ljava/lang/String;)V",
+            &[JValue::Object(&context), JValue::Object(&channel_id)],
+        )?;
+
+        let icon = env
+            .get_static_field("android/R$drawable", "ic_dialog_info", "I")?
+            .i()?;
+        env.call_method(
+            &builder,
+            "setSmallIcon",
+            "(I)Landroid/app/Notification$Builder;",
+            &[JValue::Int(icon)],
+        )?;
+
+        let title = env.new_string(&notification.title)?;
+        env.call_method(
+            &builder,
+            "setContentTitle",
+            "(Ljava/lang/CharSequence;)Landroid/app/Notification$Builder;",
+            &[JValue::Object(&title)],
+        )?;
+
+        let body = env.new_string(&notification.body)?;
+        env.call_method(
+            &builder,
+            "setContentText",
+            "(Ljava/lang/CharSequence;)Landroid/app/Notification$Builder;",
+            &[JValue::Object(&body)],
+        )?;
+
+        let android_notification = env
+            .call_method(&builder, "build", "()Landroid/app/Notification;", &[])?
+            .l()?;
+        env.call_method(
+            &manager,
+            "notify",
+            "(ILandroid/app/Notification;)V",
+            &[
+                JValue::Int(notification.id),
+                JValue::Object(&android_notification),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Hands the notification to WorkManager, which posts it even if the app isn't running then
+    fn schedule(
+        &mut self,
+        notification: &Notification,
+        at: DateTime<Local>,
+    ) -> Result<(), Box<dyn Error>> {
+        use jni::objects::JValue;
+
+        let delay = (at - Local::now()).num_milliseconds().max(0);
+
+        with_activity(|env, context| {
+            Self::request_permission(env, context)?;
+
+            let class = load_class(env, context, Self::WORKER_CLASS)?;
+            let title = env.new_string(&notification.title)?;
+            let body = env.new_string(&notification.body)?;
+            env.call_static_method(
+                &class,
+                "schedule",
+                "(Landroid/content/Context;ILjava/lang/String;Ljava/lang/String;J)V",
+                &[
+                    JValue::Object(context),
+                    JValue::Int(notification.id),
+                    JValue::Object(&title),
+                    JValue::Object(&body),
+                    JValue::Long(delay),
+                ],
+            )?;
+
+            Ok(())
+        })
+    }
+
+    fn cancel(&mut self, id: i32) -> Result<(), Box<dyn Error>> {
+        use jni::objects::JValue;
+
+        with_activity(|env, context| {
+            let class = load_class(env, context, Self::WORKER_CLASS)?;
+            env.call_static_method(
+                &class,
+                "cancel",
+                "(Landroid/content/Context;I)V",
+                &[JValue::Object(context), JValue::Int(id)],
+            )?;
+
+            Ok(())
+        })
+    }
+
+    fn is_persistent(&self) -> bool {
+        true
+    }
+
+    /// WorkManager posts the notifications without the app, which doesn't learn when
+    fn take_fired(&mut self) -> Vec<i32> {
+        Vec::new()
+    }
+}
+
+/// Sends notifications to the desktop's notification server over D-Bus
+#[cfg(all(
+    unix,
+    not(any(target_os = "android", target_os = "macos", target_os = "ios"))
+))]
+#[derive(Default)]
+pub struct DbusNotifications {
+    timers: NotificationTimers,
+}
+
+#[cfg(all(
+    unix,
+    not(any(target_os = "android", target_os = "macos", target_os = "ios"))
+))]
+impl DbusNotifications {
+    fn send(notification: &Notification) -> Result<(), Box<dyn Error>> {
+        notify_rust::Notification::new()
+            .appname("Android Rust Experiments")
+            .id(notification.id as u32)
+            .summary(&notification.title)
+            .body(&notification.body)
+            .show()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(all(
+    unix,
+    not(any(target_os = "android", target_os = "macos", target_os = "ios"))
+))]
+impl NotificationBackend for DbusNotifications {
+    fn show(&mut self, notification: &Notification) -> Result<(), Box<dyn Error>> {
+        Self::send(notification)
+    }
+
+    fn schedule(
+        &mut self,
+        notification: &Notification,
+        at: DateTime<Local>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.timers.schedule(notification, at, Self::send)
+    }
+
+    fn cancel(&mut self, id: i32) -> Result<(), Box<dyn Error>> {
+        self.timers.cancel(id);
+
+        Ok(())
+    }
+
+    fn is_persistent(&self) -> bool {
+        false
+    }
+
+    fn take_fired(&mut self) -> Vec<i32> {
+        self.timers.take_fired()
+    }
+}
+
+/// Writes notifications to the log on platforms without a native backend
+#[derive(Default)]
+pub struct LogNotifications {
+    timers: NotificationTimers,
+}
+
+impl LogNotifications {
+    fn log(notification: &Notification) -> Result<(), Box<dyn Error>> {
+        log::info!("{}: {}", notification.title, notification.body);
+
+        Ok(())
+    }
+}
+
+impl NotificationBackend for LogNotifications {
+    fn show(&mut self, notification: &Notification) -> Result<(), Box<dyn Error>> {
+        Self::log(notification)
+    }
+
+    fn schedule(
+        &mut self,
+        notification: &Notification,
+        at: DateTime<Local>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.timers.schedule(notification, at, Self::log)
+    }
+
+    fn cancel(&mut self, id: i32) -> Result<(), Box<dyn Error>> {
+        self.timers.cancel(id);
+
+        Ok(())
+    }
+
+    fn is_persistent(&self) -> bool {
+        false
+    }
+
+    fn take_fired(&mut self) -> Vec<i32> {
+        self.timers.take_fired()
+    }
+}
+
+/// A notification and when to show it
+type ScheduledNotification = (Notification, DateTime<Local>);
+
+/// Records notifications instead of showing them, for tests
+#[derive(Clone, Default)]
+pub struct MockNotifications {
+    pub shown: Rc<RefCell<Vec<Notification>>>,
+    /// The notifications that are scheduled and not cancelled, with their time
+    pub scheduled: Rc<RefCell<Vec<ScheduledNotification>>>,
+    /// Like Android, which shows scheduled notifications after the app exited
+    pub is_persistent: bool,
+    fired: Rc<RefCell<Vec<i32>>>,
+}
+
+impl MockNotifications {
+    /// Shows the scheduled notifications as if their time had come
+    pub fn fire_scheduled(&self) {
+        for (notification, _at) in self.scheduled.borrow_mut().drain(..) {
+            self.fired.borrow_mut().push(notification.id);
+            self.shown.borrow_mut().push(notification);
+        }
+    }
+}
+
+impl NotificationBackend for MockNotifications {
+    fn show(&mut self, notification: &Notification) -> Result<(), Box<dyn Error>> {
+        self.shown.borrow_mut().push(notification.clone());
+
+        Ok(())
+    }
+
+    fn schedule(
+        &mut self,
+        notification: &Notification,
+        at: DateTime<Local>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.cancel(notification.id)?;
+        self.scheduled.borrow_mut().push((notification.clone(), at));
+
+        Ok(())
+    }
+
+    fn cancel(&mut self, id: i32) -> Result<(), Box<dyn Error>> {
+        self.scheduled
+            .borrow_mut()
+            .retain(|(notification, _at)| notification.id != id);
+
+        Ok(())
+    }
+
+    fn is_persistent(&self) -> bool {
+        self.is_persistent
+    }
+
+    fn take_fired(&mut self) -> Vec<i32> {
+        std::mem::take(&mut *self.fired.borrow_mut())
+    }
+}
@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+
+use log::LevelFilter;
+
+pub const USAGE: &str = "\
+Usage: android-rust-experiments-desktop [OPTIONS]
+
+Options:
+      --data-dir <DIR>     Where the data files are kept [default: .]
+      --mobile             Uses the mobile layout in a phone sized window
+      --log-level <LEVEL>  off, error, warn, info, debug or trace, overrides RUST_LOG
+      --screen <NAME>      The screen to start on, e.g. Dates
+  -h, --help               Prints this help
+";
+
+/// Command line options of the desktop app
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Options {
+    /// `None` keeps the files in the working directory
+    pub data_dir: Option<PathBuf>,
+    /// Forces the mobile layout and opens a phone preview window
+    pub mobile: bool,
+    pub log_level: Option<LevelFilter>,
+    pub screen: Option<String>,
+    pub help: bool,
+}
+
+impl Options {
+    /// Parses the arguments without the program name, values go after a space or `=`
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_owned(), Some(value.to_owned())),
+                None => (arg, None),
+            };
+
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("{} needs a value", flag))
+            };
+
+            match flag.as_str() {
+                "--data-dir" => options.data_dir = Some(value()?.into()),
+                "--mobile" => options.mobile = true,
+                "--log-level" => {
+                    let level = value()?;
+                    options.log_level = Some(
+                        level
+                            .parse()
+                            .map_err(|_| format!("Unknown log level {}", level))?,
+                    );
+                }
+                "--screen" => options.screen = Some(value()?),
+                "-h" | "--help" => options.help = true,
+                _ => return Err(format!("Unknown option {}", flag)),
+            }
+        }
+
+        Ok(options)
+    }
+}
@@ -0,0 +1,170 @@
+use std::{
+    collections::BTreeMap,
+    io,
+    sync::{
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use crate::storage::Storage;
+
+/// Writes are delayed at most this long while more keep coming
+const MAX_DELAY: Duration = Duration::from_secs(2);
+
+/// What happened to a write on the worker thread
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WriteOutcome {
+    Written { file: String },
+    Failed { file: String, error: String },
+}
+
+enum Command {
+    Write {
+        file: String,
+        contents: String,
+    },
+    /// Writes everything pending right away and answers when done
+    Flush(Sender<()>),
+}
+
+/// Passes writes on to another storage from a worker thread, so they never block a frame.
+///
+/// Writes to the same file within `debounce` of each other are coalesced into one, with the
+/// latest contents. The outcome of every write is passed to `report`.
+pub struct Persistence {
+    inner: Arc<dyn Storage>,
+    commands: Mutex<Sender<Command>>,
+    worker: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Persistence {
+    pub fn new(
+        inner: Arc<dyn Storage>,
+        debounce: Duration,
+        report: impl Fn(WriteOutcome) + Send + 'static,
+    ) -> Self {
+        let (commands, receiver) = mpsc::channel();
+
+        let worker_storage = inner.clone();
+        let worker = thread::Builder::new()
+            .name("persistence".to_owned())
+            .spawn(move || run_worker(&*worker_storage, receiver, debounce, report))
+            .unwrap();
+
+        Self {
+            inner,
+            commands: Mutex::new(commands),
+            worker: Mutex::new(Some(worker)),
+        }
+    }
+
+    fn send(&self, command: Command) {
+        // The worker only stops when `self` is dropped
+        self.commands.lock().unwrap().send(command).ok();
+    }
+}
+
+impl Storage for Persistence {
+    /// Reads from the inner storage after pending writes were made
+    fn read(&self, file: &str) -> Option<String> {
+        self.flush();
+
+        self.inner.read(file)
+    }
+
+    /// Queues the write, failures are only reported later
+    fn write(&self, file: &str, contents: &str) -> io::Result<()> {
+        self.send(Command::Write {
+            file: file.to_owned(),
+            contents: contents.to_owned(),
+        });
+
+        Ok(())
+    }
+
+    fn flush(&self) {
+        let (done, wait) = mpsc::channel();
+
+        self.send(Command::Flush(done));
+
+        wait.recv().ok();
+    }
+}
+
+impl Drop for Persistence {
+    fn drop(&mut self) {
+        // Closing the channel makes the worker write the rest and stop
+        let (closed, _) = mpsc::channel();
+        *self.commands.lock().unwrap() = closed;
+
+        if let Some(worker) = self.worker.lock().unwrap().take() {
+            worker.join().ok();
+        }
+    }
+}
+
+fn run_worker(
+    storage: &dyn Storage,
+    commands: Receiver<Command>,
+    debounce: Duration,
+    report: impl Fn(WriteOutcome),
+) {
+    // Contents by file, and when the first of them was queued
+    let mut pending = BTreeMap::new();
+    let mut first_queued = Instant::now();
+    let mut last_queued = Instant::now();
+
+    loop {
+        let command = if pending.is_empty() {
+            commands.recv().map_err(|_| RecvTimeoutError::Disconnected)
+        } else {
+            let deadline = (last_queued + debounce).min(first_queued + MAX_DELAY);
+            commands.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+        };
+
+        match command {
+            Ok(Command::Write { file, contents }) => {
+                if pending.is_empty() {
+                    first_queued = Instant::now();
+                }
+
+                last_queued = Instant::now();
+                pending.insert(file, contents);
+            }
+            Ok(Command::Flush(done)) => {
+                write_pending(storage, &mut pending, &report);
+                done.send(()).ok();
+            }
+            Err(RecvTimeoutError::Timeout) => write_pending(storage, &mut pending, &report),
+            Err(RecvTimeoutError::Disconnected) => {
+                write_pending(storage, &mut pending, &report);
+                return;
+            }
+        }
+    }
+}
+
+fn write_pending(
+    storage: &dyn Storage,
+    pending: &mut BTreeMap<String, String>,
+    report: &impl Fn(WriteOutcome),
+) {
+    for (file, contents) in std::mem::take(pending) {
+        let outcome = match storage.write(&file, &contents) {
+            Ok(()) => WriteOutcome::Written { file },
+            Err(err) => {
+                log::error!("Failed to write {}: {}", file, err);
+
+                WriteOutcome::Failed {
+                    file,
+                    error: err.to_string(),
+                }
+            }
+        };
+
+        report(outcome);
+    }
+}
@@ -0,0 +1,384 @@
+use winit::event::{ElementState, KeyboardInput, VirtualKeyCode};
+use winit::window::Window;
+
+#[cfg(target_os = "android")]
+use crate::constants::LOG_TODAY_ACTION;
+use crate::i18n::Language;
+
+/// Whether `input` is a press of the system back button or gesture
+pub(crate) fn is_back_key(input: &KeyboardInput) -> bool {
+    if input.state != ElementState::Pressed {
+        return false;
+    }
+
+    // winit 0.28 has no virtual key code for Android's KEYCODE_BACK and passes the scan code on,
+    // which is KEY_BACK for hardware buttons and 0 for the navigation bar and the back gesture
+    #[cfg(target_os = "android")]
+    if input.virtual_keycode.is_none() && matches!(input.scancode, 0 | 158) {
+        return true;
+    }
+
+    input.virtual_keycode == Some(VirtualKeyCode::NavigateBackward)
+}
+
+/// Leaves the app like the home button would, without finishing it
+pub(crate) fn move_to_background(window: &Window) {
+    #[cfg(target_os = "android")]
+    {
+        let _ = window;
+
+        if let Err(err) = move_task_to_back() {
+            log::warn!("Failed to move the activity to the background: {}", err);
+        }
+    }
+
+    #[cfg(not(target_os = "android"))]
+    window.set_minimized(true);
+}
+
+/// Shows `message` without egui, which can't draw when it's about the surface or the window, as
+/// a toast on Android and in the title elsewhere. Without a window it is only logged there.
+pub(crate) fn show_error(window: Option<&Window>, message: &str) {
+    #[cfg(target_os = "android")]
+    {
+        let _ = window;
+
+        if let Err(err) = with_activity(|env, activity| show_toast(env, activity, message)) {
+            log::warn!("Failed to show the error {:?}: {}", message, err);
+        }
+    }
+
+    #[cfg(not(target_os = "android"))]
+    match window {
+        Some(window) => window.set_title(&format!("{} – {}", crate::APP_TITLE, message)),
+        None => log::error!("{}", message),
+    }
+}
+
+/// Offers "Log today" as an app shortcut, on a long press of the launcher icon
+pub(crate) fn register_shortcuts(label: &str) {
+    #[cfg(target_os = "android")]
+    if let Err(err) = with_activity(|env, activity| set_log_today_shortcut(env, activity, label)) {
+        log::warn!("Failed to register the app shortcuts: {}", err);
+    }
+
+    #[cfg(not(target_os = "android"))]
+    let _ = label;
+}
+
+/// The language of the locale in the Android configuration, `None` if there is no catalog for it
+#[cfg(target_os = "android")]
+pub(crate) fn system_language() -> Option<Language> {
+    match with_activity(configuration_locale) {
+        Ok(locale) => Language::from_locale(&locale),
+        Err(err) => {
+            log::warn!("Failed to read the locale: {}", err);
+            None
+        }
+    }
+}
+
+/// The language of the locale in `LC_ALL`, `LC_MESSAGES` or `LANG`, the first one that is set,
+/// `None` if there is no catalog for it
+#[cfg(not(target_os = "android"))]
+pub(crate) fn system_language() -> Option<Language> {
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())?;
+
+    Language::from_locale(&locale)
+}
+
+/// Whether the app was launched by the "Log today" shortcut or an intent with
+/// `LOG_TODAY_ACTION`, only once per launch.
+///
+/// Only the intent that created the activity is seen, NativeActivity drops the ones of
+/// `onNewIntent`. The shortcut always creates a new activity, intents of other apps need
+/// `FLAG_ACTIVITY_CLEAR_TASK` while the app runs.
+#[cfg(target_os = "android")]
+pub(crate) fn take_log_today_launch() -> bool {
+    match with_activity(take_log_today_action) {
+        Ok(is_log_today) => is_log_today,
+        Err(err) => {
+            log::warn!("Failed to read the launch intent: {}", err);
+            false
+        }
+    }
+}
+
+#[cfg(not(target_os = "android"))]
+pub(crate) fn take_log_today_launch() -> bool {
+    false
+}
+
+/// Closes the activity, e.g. after a launch that needs no UI
+pub(crate) fn finish_activity() {
+    #[cfg(target_os = "android")]
+    if let Err(err) = with_activity(|env, activity| {
+        env.call_method(activity, "finish", "()V", &[])?;
+        Ok(())
+    }) {
+        log::warn!("Failed to finish the activity: {}", err);
+    }
+}
+
+/// The local references a call through `with_activity` is expected to need
+#[cfg(target_os = "android")]
+const LOCAL_REFERENCES: i32 = 16;
+
+#[cfg(target_os = "android")]
+pub(crate) fn with_activity<T>(
+    f: impl FnOnce(&mut jni::JNIEnv, &jni::objects::JObject) -> jni::errors::Result<T>,
+) -> Result<T, Box<dyn std::error::Error>> {
+    use jni::objects::JObject;
+
+    let android_context = ndk_context::android_context();
+    let vm = unsafe { jni::JavaVM::from_raw(android_context.vm().cast()) }?;
+    let mut env = vm.attach_current_thread()?;
+    let activity = unsafe { JObject::from_raw(android_context.context().cast()) };
+
+    // The main thread stays attached, its local references would pile up frame after frame
+    Ok(env.with_local_frame(LOCAL_REFERENCES, |env| f(env, &activity))?)
+}
+
+/// A class of the app like "com.example.android_rust_experiments.ReminderWorker", through the
+/// class loader of the activity, as native threads only see the classes of the system
+#[cfg(target_os = "android")]
+pub(crate) fn load_class<'local>(
+    env: &mut jni::JNIEnv<'local>,
+    activity: &jni::objects::JObject,
+    name: &str,
+) -> jni::errors::Result<jni::objects::JClass<'local>> {
+    use jni::objects::{JClass, JValue};
+
+    let class_loader = env
+        .call_method(activity, "getClassLoader", "()Ljava/lang/ClassLoader;", &[])?
+        .l()?;
+    let name = env.new_string(name)?;
+    let class = env
+        .call_method(
+            &class_loader,
+            "loadClass",
+            "(Ljava/lang/String;)Ljava/lang/Class;",
+            &[JValue::Object(&name)],
+        )?
+        .l()?;
+
+    Ok(JClass::from(class))
+}
+
+#[cfg(target_os = "android")]
+fn show_toast(
+    env: &mut jni::JNIEnv,
+    activity: &jni::objects::JObject,
+    message: &str,
+) -> jni::errors::Result<()> {
+    use jni::objects::JValue;
+
+    let class = load_class(env, activity, "com.example.android_rust_experiments.Toasts")?;
+    let message = env.new_string(message)?;
+    env.call_static_method(
+        &class,
+        "show",
+        "(Landroid/app/Activity;Ljava/lang/String;)V",
+        &[JValue::Object(activity), JValue::Object(&message)],
+    )?;
+
+    Ok(())
+}
+
+#[cfg(target_os = "android")]
+fn move_task_to_back() -> Result<(), Box<dyn std::error::Error>> {
+    use jni::objects::JValue;
+
+    with_activity(|env, activity| {
+        env.call_method(
+            activity,
+            "moveTaskToBack",
+            "(Z)Z",
+            &[JValue::Bool(true.into())],
+        )?;
+
+        Ok(())
+    })
+}
+
+/// `Intent.FLAG_ACTIVITY_NEW_TASK`
+#[cfg(target_os = "android")]
+const FLAG_ACTIVITY_NEW_TASK: i32 = 0x1000_0000;
+/// `Intent.FLAG_ACTIVITY_CLEAR_TASK`
+#[cfg(target_os = "android")]
+const FLAG_ACTIVITY_CLEAR_TASK: i32 = 0x0000_8000;
+
+/// Publishes a dynamic shortcut that starts the activity with `LOG_TODAY_ACTION`
+#[cfg(target_os = "android")]
+fn set_log_today_shortcut(
+    env: &mut jni::JNIEnv,
+    activity: &jni::objects::JObject,
+    label: &str,
+) -> jni::errors::Result<()> {
+    use jni::objects::JValue;
+
+    let class = env
+        .call_method(activity, "getClass", "()Ljava/lang/Class;", &[])?
+        .l()?;
+    let action = env.new_string(LOG_TODAY_ACTION)?;
+    let intent = env.new_object(
+        "android/content/Intent",
+        "(Ljava/lang/String;)V",
+        &[JValue::Object(&action)],
+    )?;
+    env.call_method(
+        &intent,
+        "setClass",
+        "(Landroid/content/Context;Ljava/lang/Class;)Landroid/content/Intent;",
+        &[JValue::Object(activity), JValue::Object(&class)],
+    )?;
+    // A fresh activity gets the intent from `getIntent`, a running one only in `onNewIntent`,
+    // which NativeActivity doesn't pass on
+    env.call_method(
+        &intent,
+        "addFlags",
+        "(I)Landroid/content/Intent;",
+        &[JValue::Int(
+            FLAG_ACTIVITY_NEW_TASK | FLAG_ACTIVITY_CLEAR_TASK,
+        )],
+    )?;
+
+    let id = env.new_string("log_today")?;
+    let label = env.new_string(label)?;
+    let builder = env.new_object(
+        "android/content/pm/ShortcutInfo$Builder",
+        "(Landroid/content/Context;Ljava/lang/String;)V",
+        &[JValue::Object(activity), JValue::Object(&id)],
+    )?;
+    env.call_method(
+        &builder,
+        "setShortLabel",
+        "(Ljava/lang/CharSequence;)Landroid/content/pm/ShortcutInfo$Builder;",
+        &[JValue::Object(&label)],
+    )?;
+    env.call_method(
+        &builder,
+        "setIntent",
+        "(Landroid/content/Intent;)Landroid/content/pm/ShortcutInfo$Builder;",
+        &[JValue::Object(&intent)],
+    )?;
+    let shortcut = env
+        .call_method(
+            &builder,
+            "build",
+            "()Landroid/content/pm/ShortcutInfo;",
+            &[],
+        )?
+        .l()?;
+
+    let shortcuts = env.new_object("java/util/ArrayList", "()V", &[])?;
+    env.call_method(
+        &shortcuts,
+        "add",
+        "(Ljava/lang/Object;)Z",
+        &[JValue::Object(&shortcut)],
+    )?;
+
+    let manager_class = env.find_class("android/content/pm/ShortcutManager")?;
+    let manager = env
+        .call_method(
+            activity,
+            "getSystemService",
+            "(Ljava/lang/Class;)Ljava/lang/Object;",
+            &[JValue::Object(&manager_class)],
+        )?
+        .l()?;
+    env.call_method(
+        &manager,
+        "setDynamicShortcuts",
+        "(Ljava/util/List;)Z",
+        &[JValue::Object(&shortcuts)],
+    )?;
+
+    Ok(())
+}
+
+#[cfg(target_os = "android")]
+fn take_log_today_action(
+    env: &mut jni::JNIEnv,
+    activity: &jni::objects::JObject,
+) -> jni::errors::Result<bool> {
+    use jni::objects::{JObject, JString, JValue};
+
+    let intent = env
+        .call_method(activity, "getIntent", "()Landroid/content/Intent;", &[])?
+        .l()?;
+
+    if intent.is_null() {
+        return Ok(false);
+    }
+
+    let action = env
+        .call_method(&intent, "getAction", "()Ljava/lang/String;", &[])?
+        .l()?;
+
+    if action.is_null() {
+        return Ok(false);
+    }
+
+    let action: String = env.get_string(&JString::from(action))?.into();
+
+    if action != LOG_TODAY_ACTION {
+        return Ok(false);
+    }
+
+    // Resuming the activity again must not log another event
+    env.call_method(
+        &intent,
+        "setAction",
+        "(Ljava/lang/String;)Landroid/content/Intent;",
+        &[JValue::Object(&JObject::null())],
+    )?;
+
+    Ok(true)
+}
+
+/// The first locale of the configuration as a language tag like "de-AT"
+#[cfg(target_os = "android")]
+fn configuration_locale(
+    env: &mut jni::JNIEnv,
+    activity: &jni::objects::JObject,
+) -> jni::errors::Result<String> {
+    use jni::objects::{JString, JValue};
+
+    let resources = env
+        .call_method(
+            activity,
+            "getResources",
+            "()Landroid/content/res/Resources;",
+            &[],
+        )?
+        .l()?;
+    let configuration = env
+        .call_method(
+            &resources,
+            "getConfiguration",
+            "()Landroid/content/res/Configuration;",
+            &[],
+        )?
+        .l()?;
+    let locales = env
+        .call_method(
+            &configuration,
+            "getLocales",
+            "()Landroid/os/LocaleList;",
+            &[],
+        )?
+        .l()?;
+    let locale = env
+        .call_method(&locales, "get", "(I)Ljava/util/Locale;", &[JValue::Int(0)])?
+        .l()?;
+    let tag = env
+        .call_method(&locale, "toLanguageTag", "()Ljava/lang/String;", &[])?
+        .l()?;
+
+    Ok(env.get_string(&JString::from(tag))?.into())
+}
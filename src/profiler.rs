@@ -0,0 +1,295 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Write,
+    io,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use egui::{epaint::Primitive, ClippedPrimitive, TextureId, TexturesDelta};
+
+use crate::storage::Storage;
+
+/// How many frames the overlay averages over
+const HISTORY: usize = 120;
+
+/// Where recorded traces are written, in the chrome trace format
+pub const TRACE_FILE: &str = "trace.json";
+
+/// CPU time of the phases of a frame
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FrameTimes {
+    /// Running the UI code
+    pub ui: Duration,
+    pub tessellate: Duration,
+    /// Uploading textures and submitting the meshes
+    pub paint: Duration,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FrameProfile {
+    pub times: FrameTimes,
+    pub meshes: usize,
+    pub vertices: usize,
+    /// Bytes of all textures alive after the frame
+    pub texture_bytes: usize,
+}
+
+/// Collects the costs of the frames, shows them in an overlay and records them as a trace.
+///
+/// The overlay is toggled with F12 or a three finger touch.
+pub struct Profiler {
+    /// The start and profile of the recent frames
+    frames: VecDeque<(Instant, FrameProfile)>,
+    /// Bytes by texture, textures are RGBA on the GPU
+    textures: HashMap<TextureId, usize>,
+    is_overlay_visible: bool,
+    /// Set while three fingers touch, so one touch toggles once
+    is_gesture_active: bool,
+    /// The frames since recording started
+    trace: Option<Vec<(Instant, FrameProfile)>>,
+    /// Where traces are written
+    storage: Arc<dyn Storage>,
+}
+
+impl Profiler {
+    pub fn new(storage: Arc<dyn Storage>) -> Self {
+        Self {
+            frames: VecDeque::with_capacity(HISTORY),
+            textures: HashMap::new(),
+            is_overlay_visible: false,
+            is_gesture_active: false,
+            trace: None,
+            storage,
+        }
+    }
+
+    pub fn frame_finished(
+        &mut self,
+        started: Instant,
+        times: FrameTimes,
+        primitives: &[ClippedPrimitive],
+        textures_delta: &TexturesDelta,
+    ) {
+        for (id, delta) in &textures_delta.set {
+            // Partial updates keep the size of the texture
+            if delta.pos.is_none() {
+                let [width, height] = delta.image.size();
+                self.textures.insert(*id, width * height * 4);
+            }
+        }
+
+        for id in &textures_delta.free {
+            self.textures.remove(id);
+        }
+
+        let meshes = primitives
+            .iter()
+            .filter_map(|primitive| match &primitive.primitive {
+                Primitive::Mesh(mesh) => Some(mesh),
+                Primitive::Callback(_) => None,
+            });
+
+        let profile = FrameProfile {
+            times,
+            meshes: meshes.clone().count(),
+            vertices: meshes.map(|mesh| mesh.vertices.len()).sum(),
+            texture_bytes: self.textures.values().sum(),
+        };
+
+        if self.frames.len() == HISTORY {
+            self.frames.pop_front();
+        }
+
+        self.frames.push_back((started, profile));
+
+        if let Some(trace) = &mut self.trace {
+            trace.push((started, profile));
+        }
+    }
+
+    /// The most recent frame
+    pub fn last_frame(&self) -> Option<FrameProfile> {
+        self.frames.back().map(|(_, profile)| *profile)
+    }
+
+    /// Frames per second over the recent frames
+    pub fn fps(&self) -> f32 {
+        match (self.frames.front(), self.frames.back()) {
+            (Some((first, _)), Some((last, _))) if last > first => {
+                (self.frames.len() - 1) as f32 / (*last - *first).as_secs_f32()
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// The average of the recent frames
+    pub fn average_times(&self) -> FrameTimes {
+        let mut sum = FrameTimes::default();
+
+        for (_, profile) in &self.frames {
+            sum.ui += profile.times.ui;
+            sum.tessellate += profile.times.tessellate;
+            sum.paint += profile.times.paint;
+        }
+
+        let frames = self.frames.len().max(1) as u32;
+
+        FrameTimes {
+            ui: sum.ui / frames,
+            tessellate: sum.tessellate / frames,
+            paint: sum.paint / frames,
+        }
+    }
+
+    pub fn is_overlay_visible(&self) -> bool {
+        self.is_overlay_visible
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.trace.is_some()
+    }
+
+    pub fn start_recording(&mut self) {
+        self.trace = Some(Vec::new());
+    }
+
+    /// Writes the frames since `start_recording` to `TRACE_FILE`
+    pub fn stop_recording(&mut self) -> io::Result<()> {
+        match self.trace.take() {
+            Some(frames) => self.storage.write(TRACE_FILE, &chrome_trace(&frames)),
+            None => Ok(()),
+        }
+    }
+
+    /// Toggles and shows the overlay, call at the end of the frame so it is drawn on top
+    pub fn ui(&mut self, ctx: &egui::Context) {
+        if ctx.input(|input| input.key_pressed(egui::Key::F12)) {
+            self.is_overlay_visible = !self.is_overlay_visible;
+        }
+
+        let touches = ctx.multi_touch().map_or(0, |touch| touch.num_touches);
+
+        if touches >= 3 && !self.is_gesture_active {
+            self.is_overlay_visible = !self.is_overlay_visible;
+        }
+
+        self.is_gesture_active = touches >= 3;
+
+        if !self.is_overlay_visible {
+            return;
+        }
+
+        egui::Area::new("profiler")
+            .order(egui::Order::Foreground)
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-8.0, 8.0))
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| self.overlay_ui(ui));
+            });
+
+        // Keeps the numbers current, the scheduler would otherwise only draw on input
+        ctx.request_repaint();
+    }
+
+    fn overlay_ui(&mut self, ui: &mut egui::Ui) {
+        let times = self.average_times();
+        let last_frame = self.last_frame().unwrap_or_default();
+
+        ui.label(format!("{:.0} FPS", self.fps()));
+        ui.label(format!(
+            "CPU ui {:.2} ms, tessellate {:.2} ms, paint {:.2} ms",
+            millis(times.ui),
+            millis(times.tessellate),
+            millis(times.paint)
+        ));
+        ui.label(format!(
+            "{} meshes, {} vertices",
+            last_frame.meshes, last_frame.vertices
+        ));
+        ui.label(format!(
+            "{:.1} MiB in {} textures",
+            last_frame.texture_bytes as f32 / (1024.0 * 1024.0),
+            self.textures.len()
+        ));
+
+        match &self.trace {
+            Some(frames) => {
+                if ui
+                    .button(format!("Stop recording ({} frames)", frames.len()))
+                    .clicked()
+                {
+                    if let Err(err) = self.stop_recording() {
+                        log::error!("Failed to write the trace: {}", err);
+                    }
+                }
+            }
+            None => {
+                if ui.button("Record trace").clicked() {
+                    self.start_recording();
+                }
+            }
+        }
+    }
+}
+
+fn millis(duration: Duration) -> f32 {
+    duration.as_secs_f32() * 1000.0
+}
+
+/// The frames as chrome trace JSON, for chrome://tracing or https://ui.perfetto.dev
+fn chrome_trace(frames: &[(Instant, FrameProfile)]) -> String {
+    let start = match frames.first() {
+        Some((start, _)) => *start,
+        None => return "{\"traceEvents\":[]}\n".to_owned(),
+    };
+
+    let mut events = Vec::new();
+
+    for (started, profile) in frames {
+        let mut ts = (*started - start).as_micros();
+        let times = profile.times;
+        let duration = times.ui + times.tessellate + times.paint;
+
+        events.push(complete_event("frame", ts, duration));
+
+        for (name, duration) in [
+            ("ui", times.ui),
+            ("tessellate", times.tessellate),
+            ("paint", times.paint),
+        ] {
+            events.push(complete_event(name, ts, duration));
+            ts += duration.as_micros();
+        }
+
+        let ts = (*started - start).as_micros();
+        events.push(format!(
+            "{{\"name\":\"meshes\",\"ph\":\"C\",\"ts\":{},\"pid\":1,\"args\":{{\"meshes\":{},\"vertices\":{}}}}}",
+            ts, profile.meshes, profile.vertices
+        ));
+        events.push(format!(
+            "{{\"name\":\"textures\",\"ph\":\"C\",\"ts\":{},\"pid\":1,\"args\":{{\"bytes\":{}}}}}",
+            ts, profile.texture_bytes
+        ));
+    }
+
+    let mut trace = "{\"traceEvents\":[\n".to_owned();
+
+    for (i, event) in events.iter().enumerate() {
+        let separator = if i + 1 < events.len() { "," } else { "" };
+        writeln!(trace, "{}{}", event, separator).unwrap();
+    }
+
+    trace.push_str("]}\n");
+
+    trace
+}
+
+/// An event with a start and duration in µs
+fn complete_event(name: &str, ts: u128, duration: Duration) -> String {
+    format!(
+        "{{\"name\":\"{}\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":1,\"tid\":1}}",
+        name,
+        ts,
+        duration.as_micros()
+    )
+}
@@ -0,0 +1,121 @@
+use std::time::{Duration, Instant};
+
+use winit::event_loop::ControlFlow;
+
+/// How often the frame statistics are logged
+const REPORT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Counts frames and event loop wakeups, an idle app should have neither
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FrameStats {
+    pub frames: u64,
+    /// Times the event loop woke up, with or without drawing
+    pub wakeups: u64,
+    /// CPU time spent in the frames
+    pub frame_time: Duration,
+    pub max_frame_time: Duration,
+}
+
+impl FrameStats {
+    pub fn average_frame_time(&self) -> Duration {
+        match self.frames {
+            0 => Duration::ZERO,
+            frames => self.frame_time / frames as u32,
+        }
+    }
+}
+
+/// Decides when to draw, so nothing is drawn while nothing changes.
+///
+/// Frames are drawn on input, when the delay of `AppEvent::RequestRedraw` or egui's
+/// `repaint_after` is over, in between the event loop sleeps.
+pub struct RedrawScheduler {
+    /// When the next frame is due, `None` until something asks for one
+    next_frame: Option<Instant>,
+    stats: FrameStats,
+    /// Since the last report
+    recent: FrameStats,
+    last_report: Instant,
+}
+
+impl Default for RedrawScheduler {
+    fn default() -> Self {
+        Self {
+            next_frame: None,
+            stats: FrameStats::default(),
+            recent: FrameStats::default(),
+            last_report: Instant::now(),
+        }
+    }
+}
+
+impl RedrawScheduler {
+    /// Asks for a frame as soon as possible
+    pub fn request_redraw(&mut self) {
+        self.next_frame = Some(Instant::now());
+    }
+
+    /// Asks for a frame after `after`, an earlier frame that is already due stays due
+    pub fn request_redraw_after(&mut self, after: Duration) {
+        if let Some(requested) = Instant::now().checked_add(after) {
+            self.next_frame = Some(
+                self.next_frame
+                    .map_or(requested, |next| next.min(requested)),
+            );
+        }
+    }
+
+    pub fn is_due(&self, now: Instant) -> bool {
+        matches!(self.next_frame, Some(next_frame) if next_frame <= now)
+    }
+
+    pub fn control_flow(&self) -> ControlFlow {
+        match self.next_frame {
+            Some(next_frame) => ControlFlow::WaitUntil(next_frame),
+            None => ControlFlow::Wait,
+        }
+    }
+
+    pub fn on_wakeup(&mut self) {
+        self.stats.wakeups += 1;
+        self.recent.wakeups += 1;
+    }
+
+    /// Records a drawn frame and schedules the next one after `repaint_after`
+    pub fn frame_finished(&mut self, started: Instant, repaint_after: Duration) {
+        let now = Instant::now();
+        let frame_time = now - started;
+
+        for stats in [&mut self.stats, &mut self.recent] {
+            stats.frames += 1;
+            stats.frame_time += frame_time;
+            stats.max_frame_time = stats.max_frame_time.max(frame_time);
+        }
+
+        // egui uses `Duration::MAX` when it doesn't need another frame
+        self.next_frame = now.checked_add(repaint_after);
+
+        if now - self.last_report >= REPORT_INTERVAL {
+            self.report(now);
+        }
+    }
+
+    /// Everything since the start
+    pub fn stats(&self) -> FrameStats {
+        self.stats
+    }
+
+    fn report(&mut self, now: Instant) {
+        log::debug!(
+            "{} frames and {} wakeups in {:.1} s, {:.2} ms per frame on average, {:.2} ms at most",
+            self.recent.frames,
+            self.recent.wakeups,
+            (now - self.last_report).as_secs_f32(),
+            self.recent.average_frame_time().as_secs_f32() * 1000.0,
+            self.recent.max_frame_time.as_secs_f32() * 1000.0,
+        );
+
+        self.recent = FrameStats::default();
+        self.last_report = now;
+    }
+}
@@ -0,0 +1,336 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Datelike, Days, Local, Months, NaiveDate, TimeZone};
+use egui::DragValue;
+
+use crate::{
+    helper::{set_accessible_name, StateView},
+    i18n::Locale,
+    io::{read_shown_reminders, write_shown_reminders},
+    notifications::{platform_backend, Notification, NotificationBackend},
+    state::{AppState, Message, MessageBus},
+    storage::Storage,
+};
+
+/// The hour of the day reminders for a later day are shown at
+const REMINDER_HOUR: u32 = 9;
+
+/// When to remind the user about a tracker
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReminderRules {
+    /// Remind this many days before a running streak lapses at the end of the month
+    pub streak_at_risk_days: Option<u32>,
+    /// Remind when this many days have passed since the last event
+    pub recurring_days: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ReminderKind {
+    StreakAtRisk,
+    Recurring,
+}
+
+impl ReminderKind {
+    const ALL: [ReminderKind; 2] = [Self::StreakAtRisk, Self::Recurring];
+
+    pub(crate) fn key(&self) -> &'static str {
+        match self {
+            Self::StreakAtRisk => "streak_at_risk",
+            Self::Recurring => "recurring",
+        }
+    }
+
+    pub(crate) fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|kind| kind.key() == key)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Reminder {
+    pub tracker: String,
+    pub kind: ReminderKind,
+    pub due: NaiveDate,
+}
+
+impl Reminder {
+    /// Notifications with the same id replace each other, so there is one per tracker and kind.
+    /// The id is a hash of both, which stays the same across restarts.
+    pub fn notification_id(&self) -> i32 {
+        // FNV-1a
+        let hash = self.tracker.bytes().fold(0x811c_9dc5_u32, |hash, byte| {
+            (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+        });
+
+        let kind = match self.kind {
+            ReminderKind::StreakAtRisk => 0,
+            ReminderKind::Recurring => 1,
+        };
+
+        ((hash << 1 | kind) & i32::MAX as u32) as i32
+    }
+}
+
+/// Shows the reminders the rules in the `AppState` ask for, the ones for later days are scheduled
+/// with the platform
+pub struct Reminders {
+    backend: Box<dyn NotificationBackend>,
+    /// Reminders that have been shown, or handed to a backend that shows them even if the app has
+    /// exited by then. They are saved so a restart doesn't show them again.
+    shown: HashSet<Reminder>,
+    /// Reminders waiting for a timer of a backend that only shows them while the app runs, by
+    /// notification id
+    scheduled: HashMap<i32, Reminder>,
+}
+
+impl Default for Reminders {
+    fn default() -> Self {
+        Self::new(platform_backend())
+    }
+}
+
+impl Reminders {
+    pub fn new(backend: Box<dyn NotificationBackend>) -> Self {
+        Self {
+            backend,
+            shown: HashSet::new(),
+            scheduled: HashMap::new(),
+        }
+    }
+
+    /// With the reminders that were shown before the app was closed
+    pub fn load(storage: &dyn Storage, backend: Box<dyn NotificationBackend>) -> Self {
+        Self {
+            shown: read_shown_reminders(storage),
+            ..Self::new(backend)
+        }
+    }
+
+    pub fn shown(&self) -> &HashSet<Reminder> {
+        &self.shown
+    }
+
+    /// Computes when the reminders of `tracker` are due next, from the events of that tracker
+    pub fn schedule(&self, tracker: &str, state: &AppState) -> Vec<Reminder> {
+        let rules = state
+            .reminder_rules()
+            .get(tracker)
+            .copied()
+            .unwrap_or_default();
+        let today = state.today();
+        let events = state.events();
+        let mut reminders = Vec::new();
+
+        let current_month = today.with_day(1).unwrap();
+        let next_month = current_month + Months::new(1);
+
+        // The streak lapses when the current month ends without an event
+        if let Some(days_before) = rules.streak_at_risk_days {
+            if consecutive_months(tracker, state) > 0
+                && events.count_of(tracker, current_month..next_month) == 0
+            {
+                reminders.push(Reminder {
+                    tracker: tracker.to_owned(),
+                    kind: ReminderKind::StreakAtRisk,
+                    due: next_month - Days::new(days_before as u64),
+                });
+            }
+        }
+
+        if let Some(interval) = rules.recurring_days {
+            let due = match events.last_date_of(tracker, ..=today) {
+                Some(last_date) => last_date + Days::new(interval as u64),
+                None => today,
+            };
+
+            reminders.push(Reminder {
+                tracker: tracker.to_owned(),
+                kind: ReminderKind::Recurring,
+                due,
+            });
+        }
+
+        reminders
+    }
+
+    /// Shows the due reminders of every tracker and schedules the later ones, each once.
+    ///
+    /// Reminders that no longer apply, e.g. after a new event, are cancelled. Returns whether the
+    /// shown reminders have changed, they are written to `storage` then.
+    pub fn notify_due(&mut self, state: &AppState, storage: &dyn Storage) -> bool {
+        let today = state.today();
+        let mut has_changed = false;
+
+        for id in self.backend.take_fired() {
+            if let Some(reminder) = self.scheduled.remove(&id) {
+                has_changed |= self.shown.insert(reminder);
+            }
+        }
+
+        let reminders: Vec<Reminder> = state
+            .reminder_rules()
+            .keys()
+            .flat_map(|tracker| self.schedule(tracker, state))
+            .collect();
+
+        let outdated: Vec<Reminder> = self
+            .shown
+            .iter()
+            .chain(self.scheduled.values())
+            .filter(|reminder| !reminders.contains(reminder))
+            .cloned()
+            .collect();
+
+        for reminder in outdated {
+            if reminder.due > today {
+                if let Err(error) = self.backend.cancel(reminder.notification_id()) {
+                    log::warn!("Failed to cancel notification: {}", error);
+                }
+            }
+
+            has_changed |= self.shown.remove(&reminder);
+            self.scheduled.remove(&reminder.notification_id());
+        }
+
+        for reminder in reminders {
+            let id = reminder.notification_id();
+
+            if self.shown.contains(&reminder) || self.scheduled.contains_key(&id) {
+                continue;
+            }
+
+            let notification = notification(&reminder, state);
+
+            if reminder.due > today {
+                let at = reminder
+                    .due
+                    .and_hms_opt(REMINDER_HOUR, 0, 0)
+                    .and_then(|at| Local.from_local_datetime(&at).earliest());
+
+                let result = match at {
+                    Some(at) => self.backend.schedule(&notification, at),
+                    None => Err("the time doesn't exist in the local time zone".into()),
+                };
+
+                if let Err(error) = result {
+                    log::warn!("Failed to schedule notification: {}", error);
+                } else if self.backend.is_persistent() {
+                    has_changed |= self.shown.insert(reminder);
+                } else {
+                    self.scheduled.insert(id, reminder);
+                }
+            } else {
+                if let Err(error) = self.backend.show(&notification) {
+                    log::warn!("Failed to show notification: {}", error);
+                }
+
+                has_changed |= self.shown.insert(reminder);
+            }
+        }
+
+        if has_changed {
+            if let Err(err) = write_shown_reminders(storage, &self.shown) {
+                log::warn!("Failed to save the shown reminders: {}", err);
+            }
+        }
+
+        has_changed
+    }
+}
+
+fn notification(reminder: &Reminder, state: &AppState) -> Notification {
+    let locale = state.locale();
+    let id = reminder.notification_id();
+    let tracker = [("tracker", reminder.tracker.as_str().into())];
+
+    match reminder.kind {
+        ReminderKind::StreakAtRisk => Notification {
+            id,
+            title: locale.text_with("notification-streak-at-risk", &tracker),
+            body: locale.text_with(
+                "notification-streak-at-risk-body",
+                &[("count", consecutive_months(&reminder.tracker, state).into())],
+            ),
+        },
+        ReminderKind::Recurring => Notification {
+            id,
+            title: locale.text_with("notification-recurring", &tracker),
+            body: locale.text("notification-recurring-body"),
+        },
+    }
+}
+
+fn consecutive_months(tracker: &str, state: &AppState) -> usize {
+    let tracker_months = &state.statistics().tracker_months;
+    tracker_months.get(tracker).copied().unwrap_or_default()
+}
+
+/// Edits the reminder rules of every tracker
+#[derive(Default)]
+pub struct RemindersView;
+
+impl StateView for RemindersView {
+    fn ui(&mut self, ui: &mut egui::Ui, state: &AppState, bus: &mut MessageBus) {
+        let locale = state.locale();
+
+        for (tracker, rules) in state.reminder_rules() {
+            let mut rules = *rules;
+            let mut has_rules_changed = false;
+
+            let title = locale.text_with("reminders", &[("tracker", tracker.as_str().into())]);
+
+            ui.collapsing(title, |ui| {
+                has_rules_changed |= rule_ui(
+                    ui,
+                    locale,
+                    &locale.text("reminder-streak-at-risk"),
+                    &mut rules.streak_at_risk_days,
+                    3,
+                    1..=27,
+                );
+
+                has_rules_changed |= rule_ui(
+                    ui,
+                    locale,
+                    &locale.text("reminder-recurring"),
+                    &mut rules.recurring_days,
+                    14,
+                    1..=365,
+                );
+            });
+
+            if has_rules_changed {
+                bus.send(Message::ReminderRulesChanged(tracker.clone(), rules));
+            }
+        }
+    }
+}
+
+/// A checkbox to enable the rule and the number of days it uses
+fn rule_ui(
+    ui: &mut egui::Ui,
+    locale: &Locale,
+    text: &str,
+    days: &mut Option<u32>,
+    default_days: u32,
+    range: std::ops::RangeInclusive<u32>,
+) -> bool {
+    let mut is_enabled = days.is_some();
+    let mut value = days.unwrap_or(default_days);
+    let mut has_changed = false;
+
+    ui.horizontal(|ui| {
+        has_changed |= ui.checkbox(&mut is_enabled, text).changed();
+        let days_input = ui.add_enabled(
+            is_enabled,
+            DragValue::new(&mut value)
+                .clamp_range(range)
+                .suffix(locale.text("days-suffix")),
+        );
+        set_accessible_name(&days_input, text);
+        has_changed |= days_input.changed();
+    });
+
+    *days = is_enabled.then_some(value);
+
+    has_changed
+}
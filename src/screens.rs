@@ -0,0 +1,188 @@
+use egui::{Button, ComboBox, TextEdit};
+
+use crate::{
+    dates::Dates,
+    goals::Goals,
+    helper::{set_accessible_name, Screen, StateView},
+    home::Home,
+    reminders::RemindersView,
+    state::{AppState, Message, MessageBus},
+};
+
+/// The screens in the order of the navigation
+#[derive(Default)]
+pub(crate) struct ScreenRegistry {
+    screens: Vec<Box<dyn Screen>>,
+}
+
+impl ScreenRegistry {
+    /// The built-in screens, `Home` first
+    pub fn with_default_screens() -> Self {
+        let mut registry = Self::default();
+
+        registry.register(Box::<HomeScreen>::default());
+        registry.register(Box::<DatesScreen>::default());
+        registry.register(Box::<SettingsScreen>::default());
+
+        registry
+    }
+
+    /// Adds `screen` to the end of the navigation
+    pub fn register(&mut self, screen: Box<dyn Screen>) {
+        assert!(
+            self.get_mut(screen.name()).is_none(),
+            "a screen named {:?} is already registered",
+            screen.name()
+        );
+
+        self.screens.push(screen);
+    }
+
+    pub fn first(&self) -> &'static str {
+        self.screens[0].name()
+    }
+
+    /// Names and icons for the navigation
+    pub fn entries(&self) -> Vec<(&'static str, &'static str)> {
+        self.screens
+            .iter()
+            .map(|screen| (screen.name(), screen.icon()))
+            .collect()
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Box<dyn Screen>> {
+        self.screens.iter_mut().find(|screen| screen.name() == name)
+    }
+}
+
+#[derive(Default)]
+struct HomeScreen {
+    home: Home,
+    goals: Goals,
+}
+
+impl Screen for HomeScreen {
+    fn name(&self) -> &'static str {
+        "Home"
+    }
+
+    fn icon(&self) -> &'static str {
+        "🏠"
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, state: &AppState, bus: &mut MessageBus) {
+        self.home.ui(ui, state, bus);
+        self.goals.ui(ui, state, bus);
+    }
+
+    /// The statistics next to the goals
+    fn desktop_ui(&mut self, ui: &mut egui::Ui, state: &AppState, bus: &mut MessageBus) {
+        ui.columns(2, |columns| {
+            self.home.ui(&mut columns[0], state, bus);
+            self.goals.ui(&mut columns[1], state, bus);
+        });
+    }
+}
+
+#[derive(Default)]
+struct DatesScreen {
+    dates: Dates,
+    reminders: RemindersView,
+}
+
+impl Screen for DatesScreen {
+    fn name(&self) -> &'static str {
+        "Dates"
+    }
+
+    fn icon(&self) -> &'static str {
+        "📅"
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, state: &AppState, bus: &mut MessageBus) {
+        self.dates.ui(ui, state, bus);
+        self.reminders.ui(ui, state, bus);
+    }
+
+    /// The list of events next to the selected one, the picker and the reminders
+    fn desktop_ui(&mut self, ui: &mut egui::Ui, state: &AppState, bus: &mut MessageBus) {
+        egui::SidePanel::left("dates_list")
+            .resizable(true)
+            .default_width(280.0)
+            .show_inside(ui, |ui| {
+                // The panel takes the width of its contents, which are narrower than the panel
+                ui.set_min_width(ui.available_width());
+
+                let max_height = ui.available_height();
+                self.dates.list_ui(ui, state, max_height, true);
+            });
+
+        egui::CentralPanel::default().show_inside(ui, |ui| {
+            self.dates.detail_ui(ui, state);
+            ui.separator();
+            self.dates.entry_ui(ui, state, bus);
+            self.dates.picker_ui(ui, state, bus);
+            self.reminders.ui(ui, state, bus);
+        });
+
+        self.dates.confirm_delete_ui(ui, state, bus);
+    }
+
+    fn on_leave(&mut self, _state: &AppState) {
+        // The confirmation would otherwise pop up again when coming back
+        self.dates.cancel_delete();
+    }
+}
+
+#[derive(Default)]
+struct SettingsScreen {
+    /// The name typed for a new tracker
+    new_tracker: String,
+}
+
+impl Screen for SettingsScreen {
+    fn name(&self) -> &'static str {
+        "Settings"
+    }
+
+    fn icon(&self) -> &'static str {
+        "⚙"
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, state: &AppState, bus: &mut MessageBus) {
+        let mut settings = state.settings().clone();
+
+        settings.ui(ui, state.locale(), state.system_language());
+
+        ComboBox::new("default_tracker", state.locale().text("default-tracker"))
+            .selected_text(&settings.default_tracker)
+            .show_ui(ui, |ui| {
+                for tracker in state.trackers() {
+                    ui.selectable_value(&mut settings.default_tracker, tracker.to_owned(), tracker);
+                }
+            });
+
+        ui.horizontal(|ui| {
+            let new_tracker = ui.add(
+                TextEdit::singleline(&mut self.new_tracker)
+                    .id_source("new_tracker")
+                    .hint_text(state.locale().text("new-tracker")),
+            );
+            set_accessible_name(&new_tracker, state.locale().text("new-tracker"));
+
+            let name = self.new_tracker.trim();
+            // Trackers are saved as one word
+            let is_valid = !name.is_empty() && !name.contains(char::is_whitespace);
+
+            let add = Button::new(state.locale().text("add-tracker"));
+            if ui.add_enabled(is_valid, add).clicked() {
+                settings.default_tracker = name.to_owned();
+                self.new_tracker.clear();
+            }
+        });
+
+        if settings != *state.settings() {
+            bus.send(Message::SettingsChanged(settings));
+        }
+    }
+}
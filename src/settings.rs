@@ -0,0 +1,303 @@
+use chrono::{NaiveDate, Weekday};
+use egui::{Color32, ComboBox, DragValue};
+
+use crate::{
+    constants::DEFAULT_TRACKER,
+    graphics::{Backend, GraphicsSettings, PowerPreference, PresentMode, MSAA_SAMPLES},
+    helper::set_accessible_name,
+    i18n::{Language, Locale},
+    layout::FormFactor,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Theme {
+    System,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Self::System, Self::Light, Self::Dark];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::System => "System",
+            Self::Light => "Light",
+            Self::Dark => "Dark",
+        }
+    }
+
+    /// Used for the message ids
+    pub(crate) fn key(&self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::Light => "light",
+            Self::Dark => "dark",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DateFormat {
+    /// The way the language of the locale writes dates, with the month name
+    Automatic,
+    YearMonthDay,
+    DayMonthYear,
+    MonthDayYear,
+}
+
+impl DateFormat {
+    pub const ALL: [DateFormat; 4] = [
+        Self::Automatic,
+        Self::YearMonthDay,
+        Self::DayMonthYear,
+        Self::MonthDayYear,
+    ];
+
+    fn pattern(&self) -> Option<&'static str> {
+        match self {
+            Self::Automatic => None,
+            Self::YearMonthDay => Some("%Y-%m-%d"),
+            Self::DayMonthYear => Some("%d.%m.%Y"),
+            Self::MonthDayYear => Some("%m/%d/%Y"),
+        }
+    }
+
+    pub fn format(&self, date: NaiveDate, locale: &Locale) -> String {
+        match self.pattern() {
+            Some(pattern) => date.format(pattern).to_string(),
+            None => locale.format_date(date),
+        }
+    }
+
+    /// Reads a date written in the format, `Automatic` reads the numeric one of the locale
+    pub fn parse(&self, text: &str, locale: &Locale) -> Option<NaiveDate> {
+        let pattern = self
+            .pattern()
+            .map_or_else(|| locale.date_pattern(), str::to_owned);
+
+        NaiveDate::parse_from_str(text, &pattern).ok()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settings {
+    pub theme: Theme,
+    pub accent_color: Color32,
+    /// Factor applied to all font sizes
+    pub text_scale: f32,
+    /// Replaces the language of the system, `None` follows it
+    pub language: Option<Language>,
+    /// The first day of the week for the calendar, the weekly goals and the statistics
+    pub week_start: Weekday,
+    pub date_format: DateFormat,
+    /// How many years back the date picker reaches
+    pub picker_years: u32,
+    /// How many months the event count on `Home` covers
+    pub statistic_months: u32,
+    pub confirm_delete: bool,
+    /// Replaces the detected form factor, `None` detects it
+    pub form_factor: Option<FormFactor>,
+    pub graphics: GraphicsSettings,
+    /// The tracker "Log today" and the reminders use
+    pub default_tracker: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            theme: Theme::System,
+            accent_color: Color32::from_rgb(0, 92, 128),
+            text_scale: 1.0,
+            language: None,
+            week_start: Weekday::Mon,
+            date_format: DateFormat::Automatic,
+            picker_years: 15,
+            statistic_months: 12,
+            confirm_delete: true,
+            form_factor: None,
+            graphics: GraphicsSettings::default(),
+            default_tracker: DEFAULT_TRACKER.to_owned(),
+        }
+    }
+}
+
+impl Settings {
+    /// The inputs for all settings, in the language of `locale`
+    pub fn ui(&mut self, ui: &mut egui::Ui, locale: &Locale, system_language: Language) {
+        ComboBox::new("theme", locale.text("theme"))
+            .selected_text(theme_name(locale, self.theme))
+            .show_ui(ui, |ui| {
+                for theme in Theme::ALL {
+                    ui.selectable_value(&mut self.theme, theme, theme_name(locale, theme));
+                }
+            });
+
+        ui.horizontal(|ui| {
+            ui.label(locale.text("accent-color"));
+            let accent_color = ui.color_edit_button_srgba(&mut self.accent_color);
+            set_accessible_name(&accent_color, locale.text("accent-color"));
+        });
+
+        let text_scale = ui.add(
+            DragValue::new(&mut self.text_scale)
+                .clamp_range(0.5..=2.0)
+                .speed(0.01)
+                .prefix(format!("{}: ", locale.text("text-scale"))),
+        );
+        set_accessible_name(&text_scale, locale.text("text-scale"));
+
+        let language_name = |language: Option<Language>| match language {
+            Some(language) => language.name().to_owned(),
+            None => locale.text_with(
+                "language-system",
+                &[("language", system_language.name().into())],
+            ),
+        };
+
+        ComboBox::new("language", locale.text("language"))
+            .selected_text(language_name(self.language))
+            .show_ui(ui, |ui| {
+                let languages = Language::ALL.iter().copied().map(Some);
+
+                for language in std::iter::once(None).chain(languages) {
+                    ui.selectable_value(&mut self.language, language, language_name(language));
+                }
+            });
+
+        ComboBox::new("week_start", locale.text("week-start"))
+            .selected_text(locale.weekday_name(self.week_start))
+            .show_ui(ui, |ui| {
+                let weekdays = std::iter::successors(Some(Weekday::Mon), |day| Some(day.succ()));
+                for weekday in weekdays.take(7) {
+                    ui.selectable_value(
+                        &mut self.week_start,
+                        weekday,
+                        locale.weekday_name(weekday),
+                    );
+                }
+            });
+
+        let date_format_name = |date_format: DateFormat| {
+            let example = date_format.format(NaiveDate::from_ymd_opt(2023, 4, 1).unwrap(), locale);
+
+            match date_format {
+                DateFormat::Automatic => {
+                    locale.text_with("date-format-automatic", &[("example", example.into())])
+                }
+                _ => example,
+            }
+        };
+
+        ComboBox::new("date_format", locale.text("date-format"))
+            .selected_text(date_format_name(self.date_format))
+            .show_ui(ui, |ui| {
+                for date_format in DateFormat::ALL {
+                    ui.selectable_value(
+                        &mut self.date_format,
+                        date_format,
+                        date_format_name(date_format),
+                    );
+                }
+            });
+
+        let picker_years = ui.add(
+            DragValue::new(&mut self.picker_years)
+                .clamp_range(1..=100)
+                .prefix(format!("{}: ", locale.text("picker-years"))),
+        );
+        set_accessible_name(&picker_years, locale.text("picker-years"));
+
+        let statistic_months = ui.add(
+            DragValue::new(&mut self.statistic_months)
+                .clamp_range(1..=120)
+                .prefix(format!("{}: ", locale.text("statistic-months"))),
+        );
+        set_accessible_name(&statistic_months, locale.text("statistic-months"));
+
+        ui.checkbox(&mut self.confirm_delete, locale.text("confirm-delete"));
+
+        let form_factor_name = |form_factor: Option<FormFactor>| {
+            locale.text(&format!(
+                "layout-{}",
+                form_factor.map_or("automatic", |form_factor| form_factor.key())
+            ))
+        };
+
+        ComboBox::new("layout", locale.text("layout"))
+            .selected_text(form_factor_name(self.form_factor))
+            .show_ui(ui, |ui| {
+                for form_factor in [None, Some(FormFactor::Mobile), Some(FormFactor::Desktop)] {
+                    ui.selectable_value(
+                        &mut self.form_factor,
+                        form_factor,
+                        form_factor_name(form_factor),
+                    );
+                }
+            });
+
+        ui.collapsing(locale.text("graphics"), |ui| self.graphics.ui(ui, locale));
+    }
+}
+
+fn theme_name(locale: &Locale, theme: Theme) -> String {
+    locale.text(&format!("theme-{}", theme.key()))
+}
+
+impl GraphicsSettings {
+    fn ui(&mut self, ui: &mut egui::Ui, locale: &Locale) {
+        ui.label(locale.text("graphics-restart"));
+
+        ComboBox::new("msaa", locale.text("anti-aliasing"))
+            .selected_text(format!("{}x MSAA", self.msaa_samples))
+            .show_ui(ui, |ui| {
+                for samples in MSAA_SAMPLES {
+                    ui.selectable_value(
+                        &mut self.msaa_samples,
+                        samples,
+                        format!("{}x MSAA", samples),
+                    );
+                }
+            });
+
+        let mode_name = |mode: PresentMode| locale.text(&format!("present-mode-{}", mode.key()));
+
+        ComboBox::new("present_mode", locale.text("present-mode"))
+            .selected_text(mode_name(self.present_mode))
+            .show_ui(ui, |ui| {
+                for mode in PresentMode::ALL {
+                    ui.selectable_value(&mut self.present_mode, mode, mode_name(mode));
+                }
+            });
+
+        let preference_name = |preference: PowerPreference| {
+            locale.text(&format!("power-preference-{}", preference.key()))
+        };
+
+        ComboBox::new("power_preference", locale.text("gpu"))
+            .selected_text(preference_name(self.power_preference))
+            .show_ui(ui, |ui| {
+                for preference in PowerPreference::ALL {
+                    ui.selectable_value(
+                        &mut self.power_preference,
+                        preference,
+                        preference_name(preference),
+                    );
+                }
+            });
+
+        // Only "Automatic" is translated, the others are names
+        let backend_name = |backend: Backend| match backend {
+            Backend::Auto => locale.text("backend-auto"),
+            _ => backend.name().to_owned(),
+        };
+
+        ComboBox::new("backend", locale.text("backend"))
+            .selected_text(backend_name(self.backend))
+            .show_ui(ui, |ui| {
+                for backend in Backend::ALL {
+                    ui.selectable_value(&mut self.backend, backend, backend_name(backend));
+                }
+            });
+    }
+}
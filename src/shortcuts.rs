@@ -0,0 +1,50 @@
+use egui::{Context, Id, Key, KeyboardShortcut, Modifiers};
+
+use crate::i18n::Locale;
+
+/// Focuses the date field of the Dates screen
+pub(crate) const NEW_ENTRY: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::N);
+pub(crate) const UNDO: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
+pub(crate) const REDO: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Y);
+pub(crate) const REDO_ALTERNATIVE: KeyboardShortcut = KeyboardShortcut::new(
+    Modifiers {
+        shift: true,
+        ..Modifiers::COMMAND
+    },
+    Key::Z,
+);
+pub(crate) const CHEAT_SHEET: KeyboardShortcut = KeyboardShortcut::new(Modifiers::NONE, Key::F1);
+
+/// Lists the shortcuts, with Cmd instead of Ctrl on a Mac
+pub(crate) fn cheat_sheet_ui(ctx: &Context, locale: &Locale, is_open: &mut bool) {
+    let format = |shortcut| ctx.format_shortcut(shortcut);
+
+    let rows = [
+        (format(&NEW_ENTRY), "shortcut-new-entry"),
+        (locale.text("key-enter"), "shortcut-add"),
+        ("↑ ↓".to_owned(), "shortcut-select"),
+        (locale.text("key-delete"), "shortcut-delete"),
+        (format(&UNDO), "shortcut-undo"),
+        (
+            format!("{}, {}", format(&REDO), format(&REDO_ALTERNATIVE)),
+            "shortcut-redo",
+        ),
+        (format(&CHEAT_SHEET), "shortcut-cheat-sheet"),
+        ("F12".to_owned(), "shortcut-profiler"),
+    ];
+
+    egui::Window::new(locale.text("keyboard-shortcuts"))
+        .id(Id::new("keyboard_shortcuts"))
+        .open(is_open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            egui::Grid::new("shortcuts").striped(true).show(ui, |ui| {
+                for (keys, action) in rows {
+                    ui.monospace(keys);
+                    ui.label(locale.text(action));
+                    ui.end_row();
+                }
+            });
+        });
+}
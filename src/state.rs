@@ -0,0 +1,314 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io,
+};
+
+use chrono::{Datelike, Months, NaiveDate};
+
+use crate::{
+    constants::DEFAULT_TRACKER,
+    events::{Event, Events},
+    goals::{Goal, GoalStatus},
+    i18n::{Language, Locale},
+    io::{
+        read_from_file, read_goals, read_reminder_rules, read_settings, write_goals,
+        write_reminder_rules, write_settings, write_to_file,
+    },
+    reminders::ReminderRules,
+    settings::Settings,
+    storage::Storage,
+};
+
+/// A change to the `AppState`, views send these instead of changing the state themselves
+#[derive(Clone, Debug, PartialEq)]
+pub enum Message {
+    EventAdded(Event),
+    /// Removes the most recently added event equal to this one
+    EventRemoved(Event),
+    GoalAdded(Goal),
+    /// Removes the goal at the index
+    GoalRemoved(usize),
+    ReminderRulesChanged(String, ReminderRules),
+    SettingsChanged(Settings),
+    /// The date changed, or was pinned for reproducible output
+    DayChanged(NaiveDate),
+}
+
+/// Collects the messages of a frame, `MainWindows` applies them after the frame
+#[derive(Default)]
+pub struct MessageBus {
+    messages: Vec<Message>,
+}
+
+impl MessageBus {
+    pub fn send(&mut self, message: Message) {
+        self.messages.push(message);
+    }
+
+    /// Removes the messages sent so far
+    pub fn take(&mut self) -> Vec<Message> {
+        std::mem::take(&mut self.messages)
+    }
+}
+
+/// Derived from the events, updated by `AppState::apply`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Statistics {
+    /// In how many consecutive months up to the current or previous one the user was active
+    pub consecutive_months: usize,
+    /// Events in the last `statistic_months` months
+    pub recent_events: usize,
+    /// Events in the current week, which starts on the `week_start` of the settings
+    pub week_events: usize,
+    pub goals: Vec<GoalStatus>,
+    /// The consecutive months of every tracker with reminder rules
+    pub tracker_months: BTreeMap<String, usize>,
+}
+
+/// The data all screens show
+pub struct AppState {
+    events: Events,
+    goals: Vec<Goal>,
+    reminder_rules: BTreeMap<String, ReminderRules>,
+    settings: Settings,
+    /// The language of the system, used unless the settings pick another one
+    system_language: Language,
+    locale: Locale,
+    today: NaiveDate,
+    statistics: Statistics,
+}
+
+impl AppState {
+    /// Creates the state from unsorted events
+    pub fn new(
+        events: Vec<Event>,
+        goals: Vec<Goal>,
+        mut reminder_rules: BTreeMap<String, ReminderRules>,
+        settings: Settings,
+        today: NaiveDate,
+    ) -> Self {
+        reminder_rules
+            .entry(DEFAULT_TRACKER.to_owned())
+            .or_default();
+        reminder_rules
+            .entry(settings.default_tracker.clone())
+            .or_default();
+
+        let mut state = Self {
+            events: events.into_iter().collect(),
+            goals,
+            reminder_rules,
+            locale: Locale::new(settings.language.unwrap_or(Language::English)),
+            settings,
+            system_language: Language::English,
+            today,
+            statistics: Statistics::default(),
+        };
+
+        state.update_statistics();
+
+        state
+    }
+
+    pub fn load(storage: &dyn Storage, today: NaiveDate) -> Self {
+        Self::new(
+            read_from_file(storage),
+            read_goals(storage),
+            read_reminder_rules(storage),
+            read_settings(storage),
+            today,
+        )
+    }
+
+    pub fn events(&self) -> &Events {
+        &self.events
+    }
+
+    pub fn goals(&self) -> &[Goal] {
+        &self.goals
+    }
+
+    pub fn reminder_rules(&self) -> &BTreeMap<String, ReminderRules> {
+        &self.reminder_rules
+    }
+
+    /// Every tracker with events or reminder rules and the default one, sorted
+    pub fn trackers(&self) -> BTreeSet<&str> {
+        self.events
+            .trackers()
+            .chain(self.reminder_rules.keys().map(String::as_str))
+            .chain(std::iter::once(self.settings.default_tracker.as_str()))
+            .collect()
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn system_language(&self) -> Language {
+        self.system_language
+    }
+
+    /// The language of the texts and dates
+    pub fn locale(&self) -> &Locale {
+        &self.locale
+    }
+
+    /// `date` in the date format of the settings
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        self.settings.date_format.format(date, &self.locale)
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        self.today
+    }
+
+    pub fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
+
+    pub fn apply(&mut self, message: &Message) {
+        match message {
+            Message::EventAdded(event) => {
+                self.events.insert(event.clone());
+            }
+            Message::EventRemoved(event) => {
+                self.events.remove(event);
+            }
+            Message::GoalAdded(goal) => self.goals.push(*goal),
+            Message::GoalRemoved(index) => {
+                if *index < self.goals.len() {
+                    self.goals.remove(*index);
+                }
+            }
+            Message::ReminderRulesChanged(tracker, rules) => {
+                self.reminder_rules.insert(tracker.clone(), *rules);
+            }
+            Message::SettingsChanged(settings) => {
+                self.settings = settings.clone();
+                self.update_locale();
+            }
+            Message::DayChanged(today) => {
+                if *today == self.today {
+                    return;
+                }
+
+                self.today = *today;
+            }
+        }
+
+        self.update_statistics();
+    }
+
+    /// Writes the file `message` changed
+    pub(crate) fn save(&self, storage: &dyn Storage, message: &Message) -> io::Result<()> {
+        match message {
+            Message::EventAdded(_) | Message::EventRemoved(_) => {
+                write_to_file(storage, self.events.iter())
+            }
+            Message::GoalAdded(_) | Message::GoalRemoved(_) => write_goals(storage, &self.goals),
+            Message::ReminderRulesChanged(..) => {
+                write_reminder_rules(storage, &self.reminder_rules)
+            }
+            Message::SettingsChanged(_) => write_settings(storage, &self.settings),
+            Message::DayChanged(_) => Ok(()),
+        }
+    }
+
+    /// Writes all files
+    pub(crate) fn save_all(&self, storage: &dyn Storage) -> io::Result<()> {
+        write_to_file(storage, self.events.iter())?;
+        write_goals(storage, &self.goals)?;
+        write_reminder_rules(storage, &self.reminder_rules)?;
+        write_settings(storage, &self.settings)
+    }
+
+    /// Follows the language of the system where the settings leave it open
+    pub(crate) fn set_system_language(&mut self, language: Language) {
+        self.system_language = language;
+        self.update_locale();
+    }
+
+    fn update_locale(&mut self) {
+        let language = self.settings.language.unwrap_or(self.system_language);
+
+        if language != self.locale.language() {
+            self.locale = Locale::new(language);
+        }
+    }
+
+    fn update_statistics(&mut self) {
+        self.statistics = Statistics {
+            consecutive_months: consecutive_months(self.today, |end| {
+                self.events.last_date_in(..end)
+            }),
+            recent_events: self.recent_events(),
+            week_events: self.week_events(),
+            goals: self
+                .goals
+                .iter()
+                .map(|goal| goal.status(&self.events, self.today, self.settings.week_start))
+                .collect(),
+            tracker_months: self
+                .reminder_rules
+                .keys()
+                .map(|tracker| {
+                    let months = consecutive_months(self.today, |end| {
+                        self.events.last_date_of(tracker, ..end)
+                    });
+                    (tracker.clone(), months)
+                })
+                .collect(),
+        };
+    }
+
+    /// Counts the events since the start of the statistic window up to the end of the current year
+    fn recent_events(&self) -> usize {
+        let first_counted = first_of_month(self.today)
+            - Months::new(self.settings.statistic_months.saturating_sub(1));
+        let first_not_counted = NaiveDate::from_ymd_opt(self.today.year() + 1, 1, 1).unwrap();
+
+        self.events.count(first_counted..first_not_counted)
+    }
+
+    fn week_events(&self) -> usize {
+        let week = self.today.week(self.settings.week_start);
+
+        self.events.count(week.first_day()..=week.last_day())
+    }
+}
+
+/// In how many consecutive months up to the current or previous one `last_date_before` finds an
+/// event, it returns the latest event date before the given one
+fn consecutive_months(
+    today: NaiveDate,
+    last_date_before: impl Fn(NaiveDate) -> Option<NaiveDate>,
+) -> usize {
+    let current_month = first_of_month(today);
+    let previous_month = current_month - Months::new(1);
+
+    // The streak only counts if it reaches into the current or the previous month
+    let mut month = match last_date_before(current_month + Months::new(1)) {
+        Some(date) if date >= previous_month => first_of_month(date),
+        _ => return 0,
+    };
+
+    let mut consecutive_months = 1;
+
+    while let Some(date) = last_date_before(month) {
+        let previous_month = month - Months::new(1);
+
+        if date < previous_month {
+            break;
+        }
+
+        consecutive_months += 1;
+        month = previous_month;
+    }
+
+    consecutive_months
+}
+
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap()
+}
@@ -0,0 +1,71 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use crate::constants::STORAGE_PATH;
+
+/// Where the `.dat` files are kept
+pub trait Storage: Send + Sync {
+    /// The contents of `file`, `None` if it does not exist yet or can't be read
+    fn read(&self, file: &str) -> Option<String>;
+
+    fn write(&self, file: &str, contents: &str) -> io::Result<()>;
+
+    /// Blocks until earlier writes have reached their destination
+    fn flush(&self) {}
+}
+
+/// Files in a directory, `STORAGE_PATH` by default
+pub struct FileStorage {
+    dir: PathBuf,
+}
+
+impl FileStorage {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl Default for FileStorage {
+    fn default() -> Self {
+        Self::new(STORAGE_PATH)
+    }
+}
+
+impl Storage for FileStorage {
+    fn read(&self, file: &str) -> Option<String> {
+        fs::read_to_string(self.dir.join(file)).ok()
+    }
+
+    fn write(&self, file: &str, contents: &str) -> io::Result<()> {
+        if !self.dir.exists() {
+            fs::create_dir_all(&self.dir)?;
+        }
+
+        fs::write(self.dir.join(file), contents)
+    }
+}
+
+/// Keeps the files in memory, clones share them, so tests can look at what was written
+#[derive(Clone, Default)]
+pub struct MemoryStorage {
+    pub files: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl Storage for MemoryStorage {
+    fn read(&self, file: &str) -> Option<String> {
+        self.files.lock().unwrap().get(file).cloned()
+    }
+
+    fn write(&self, file: &str, contents: &str) -> io::Result<()> {
+        self.files
+            .lock()
+            .unwrap()
+            .insert(file.to_owned(), contents.to_owned());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+use egui::{Color32, Context, FontFamily::Proportional, FontId, Style, TextStyle::*, Visuals};
+
+use crate::{
+    layout::{Breakpoint, Typography},
+    settings::{Settings, Theme},
+};
+
+/// The appearance the operating system asks for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SystemTheme {
+    Light,
+    Dark,
+}
+
+impl From<winit::window::Theme> for SystemTheme {
+    fn from(theme: winit::window::Theme) -> Self {
+        match theme {
+            winit::window::Theme::Light => Self::Light,
+            winit::window::Theme::Dark => Self::Dark,
+        }
+    }
+}
+
+/// Everything the style is built from
+#[derive(Clone, Copy, PartialEq)]
+struct StyleInputs {
+    theme: Theme,
+    accent_color: Color32,
+    typography: Typography,
+    system_theme: SystemTheme,
+}
+
+/// Builds the egui style and only rebuilds it when the settings, the system appearance or the
+/// window size class change
+pub struct Themes {
+    system_theme: SystemTheme,
+    applied: Option<StyleInputs>,
+}
+
+impl Default for Themes {
+    fn default() -> Self {
+        Self {
+            // egui's own default
+            system_theme: SystemTheme::Dark,
+            applied: None,
+        }
+    }
+}
+
+impl Themes {
+    pub fn set_system_theme(&mut self, system_theme: SystemTheme) {
+        self.system_theme = system_theme;
+    }
+
+    pub fn apply(&mut self, ctx: &Context, settings: &Settings) {
+        let logical_size = ctx.input(|i| i.screen_rect.size());
+        let breakpoint = Breakpoint::from_logical_size(logical_size);
+
+        let inputs = StyleInputs {
+            theme: settings.theme,
+            accent_color: settings.accent_color,
+            typography: Typography::new(breakpoint, logical_size, settings.text_scale),
+            system_theme: self.system_theme,
+        };
+
+        if self.applied == Some(inputs) {
+            return;
+        }
+
+        ctx.set_style(build_style(&inputs));
+        self.applied = Some(inputs);
+    }
+}
+
+fn build_style(inputs: &StyleInputs) -> Style {
+    let typography = inputs.typography;
+
+    let is_dark = match inputs.theme {
+        Theme::System => inputs.system_theme == SystemTheme::Dark,
+        Theme::Light => false,
+        Theme::Dark => true,
+    };
+
+    let mut style = Style {
+        visuals: visuals(is_dark, inputs.accent_color),
+        ..Default::default()
+    };
+
+    style.text_styles = [
+        (Heading, FontId::new(typography.heading, Proportional)),
+        (
+            Name("Context".into()),
+            FontId::new(typography.button, Proportional),
+        ),
+        (Body, FontId::new(typography.body, Proportional)),
+        (Monospace, FontId::new(typography.monospace, Proportional)),
+        (Button, FontId::new(typography.button, Proportional)),
+        (Small, FontId::new(typography.small, Proportional)),
+        (
+            Name("DateInputButton".into()),
+            FontId::new(typography.date_input, Proportional),
+        ),
+        (
+            Name("NavigationButton".into()),
+            FontId::new(typography.navigation, Proportional),
+        ),
+        (
+            Name("Statistic".into()),
+            FontId::new(typography.statistic, Proportional),
+        ),
+    ]
+    .into();
+
+    style
+}
+
+fn visuals(is_dark: bool, accent_color: Color32) -> Visuals {
+    let mut visuals = if is_dark {
+        Visuals::dark()
+    } else {
+        Visuals::light()
+    };
+
+    visuals.selection.bg_fill = accent_color;
+    visuals.hyperlink_color = accent_color;
+    visuals.widgets.hovered.bg_stroke.color = accent_color;
+    visuals.widgets.active.bg_fill = accent_color;
+
+    visuals
+}
@@ -1,90 +1,334 @@
-use egui::{Button, CentralPanel, Context, FontId, Id, RichText};
+use std::sync::Arc;
 
-use egui::TextStyle::*;
-
-use egui::FontFamily::Proportional;
+use chrono::{Local, NaiveDate};
+use egui::{Button, CentralPanel, Context, Id, RichText, SelectableLabel, TextStyle};
 
 use crate::helper::Demo;
 use crate::{
     about::About,
-    dates::Dates,
-    helper::{is_mobile, View},
-    home::Home,
+    events::Event,
+    helper::{set_accessible_name, Screen},
+    history::History,
+    i18n::Language,
+    layout::FormFactor,
+    navigation::Navigation,
+    notifications::{platform_backend, NotificationBackend},
+    persistence::WriteOutcome,
+    reminders::Reminders,
+    screens::ScreenRegistry,
+    shortcuts,
+    state::{AppState, Message, MessageBus},
+    storage::{FileStorage, Storage},
+    theme::{SystemTheme, Themes},
 };
 
-#[derive(Default)]
-enum MainWindowState {
-    #[default]
-    Main,
-    Dates,
-}
-
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[cfg_attr(feature = "serde", serde(default))]
 pub struct MainWindows {
-    main_window_state: MainWindowState,
-    home: Home,
-    dates: Dates,
+    navigation: Navigation,
+    screens: ScreenRegistry,
+    /// The screen the last frame showed, to call the lifecycle hooks on a change
+    visible_screen: Option<&'static str>,
+    state: AppState,
+    /// Messages the screens sent during the frame
+    bus: MessageBus,
+    /// The changes to the events, for undo and redo
+    history: History,
+    reminders: Reminders,
+    /// Where the state is saved
+    storage: Arc<dyn Storage>,
+    /// The last failed write, until the user dismisses it
+    save_error: Option<String>,
+    themes: Themes,
     about: About,
     is_about_open: bool,
+    is_shortcuts_open: bool,
+    is_quit_requested: bool,
+    /// Replaces the system date, see `pin_today`
+    pinned_today: Option<NaiveDate>,
+    /// Whether the last pointer input came from a touch screen
+    is_touch: bool,
+    /// Replaces the settings and the detection, see `force_form_factor`
+    forced_form_factor: Option<FormFactor>,
+    /// The layout of the last frame
+    form_factor: FormFactor,
 }
 
 impl Default for MainWindows {
     fn default() -> Self {
+        Self::load(Arc::new(FileStorage::default()), platform_backend())
+    }
+}
+
+impl MainWindows {
+    /// Reads everything from `storage` and writes changes back to it
+    pub fn load(storage: Arc<dyn Storage>, notifications: Box<dyn NotificationBackend>) -> Self {
+        let state = AppState::load(&*storage, Local::now().date_naive());
+
+        Self::new(storage, state, notifications)
+    }
+
+    /// Creates the windows from an already loaded state, changes are written to `storage`
+    pub fn new(
+        storage: Arc<dyn Storage>,
+        state: AppState,
+        notifications: Box<dyn NotificationBackend>,
+    ) -> Self {
+        let screens = ScreenRegistry::with_default_screens();
+
         Self {
-            main_window_state: Default::default(),
-            home: Default::default(),
-            dates: Default::default(),
+            navigation: Navigation::new(screens.first()),
+            screens,
+            visible_screen: None,
+            state,
+            bus: MessageBus::default(),
+            history: History::default(),
+            reminders: Reminders::load(&*storage, notifications),
+            storage,
+            save_error: None,
+            themes: Default::default(),
             about: Default::default(),
             is_about_open: true,
+            is_shortcuts_open: false,
+            is_quit_requested: false,
+            pinned_today: None,
+            is_touch: cfg!(target_os = "android"),
+            forced_form_factor: None,
+            form_factor: FormFactor::Mobile,
         }
     }
-}
 
-impl MainWindows {
+    /// Adds `screen` to the end of the navigation
+    pub fn register(&mut self, screen: Box<dyn Screen>) {
+        self.screens.register(screen);
+    }
+
     pub fn ui(&mut self, ctx: &Context) {
-        let mut style = (*ctx.style()).clone();
-
-        style.text_styles = [
-            (Heading, FontId::new(45.0, Proportional)),
-            (Name("Context".into()), FontId::new(23.0, Proportional)),
-            (Body, FontId::new(32.0, Proportional)),
-            (Monospace, FontId::new(14.0, Proportional)),
-            (Button, FontId::new(24.0, Proportional)),
-            (Small, FontId::new(10.0, Proportional)),
-            (
-                Name("DateInputButton".into()),
-                FontId::new(45.0, Proportional),
-            ),
-            (
-                Name("BottomBarButton".into()),
-                FontId::new(35.0, Proportional),
-            ),
-        ]
-        .into();
-
-        ctx.set_style(style);
-
-        self.home.number_of_consecutive_months = self.dates.number_of_consecutive_months;
-        self.home.number_of_events_last_year = self.dates.number_of_events_last_year;
-
-        if is_mobile() {
-            self.mobile_ui(ctx);
-        } else {
-            self.desktop_ui(ctx);
+        self.themes.apply(ctx, self.state.settings());
+
+        self.state.apply(&Message::DayChanged(self.today()));
+
+        self.reminders.notify_due(&self.state, &*self.storage);
+
+        self.update_form_factor(ctx);
+
+        if ctx.input_mut(|input| input.consume_shortcut(&shortcuts::CHEAT_SHEET)) {
+            self.is_shortcuts_open = !self.is_shortcuts_open;
+        }
+
+        match self.form_factor {
+            FormFactor::Mobile => self.mobile_ui(ctx),
+            FormFactor::Desktop => self.desktop_ui(ctx),
+        }
+
+        shortcuts::cheat_sheet_ui(ctx, self.state.locale(), &mut self.is_shortcuts_open);
+
+        for message in self.bus.take() {
+            self.history.record(&message);
+            self.apply(message);
+        }
+
+        self.undo_shortcuts(ctx);
+    }
+
+    /// Adds an event for today to the default tracker, like "Log today" on `Home`
+    pub fn log_today(&mut self) {
+        let tracker = &self.state.settings().default_tracker;
+        let message = Message::EventAdded(Event::of_tracker(self.today(), tracker));
+
+        self.history.record(&message);
+        self.apply(message);
+    }
+
+    /// Reverts the last added or deleted event, returns `false` if there is nothing to undo
+    pub fn undo(&mut self) -> bool {
+        match self.history.undo() {
+            Some(message) => {
+                self.apply(message);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Makes the last undone change again, returns `false` if there is nothing to redo
+    pub fn redo(&mut self) -> bool {
+        match self.history.redo() {
+            Some(message) => {
+                self.apply(message);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Shows failed writes of a storage that writes in the background
+    pub fn on_write_outcome(&mut self, outcome: WriteOutcome) {
+        if let WriteOutcome::Failed { file, error } = outcome {
+            self.save_error = Some(self.state.locale().text_with(
+                "save-file-error",
+                &[("file", file.into()), ("error", error.into())],
+            ));
+        }
+    }
+
+    /// The data the screens show
+    pub fn state(&self) -> &AppState {
+        &self.state
+    }
+
+    pub fn set_system_theme(&mut self, system_theme: SystemTheme) {
+        self.themes.set_system_theme(system_theme);
+    }
+
+    /// The language to use unless the settings pick one
+    pub fn set_system_language(&mut self, language: Language) {
+        self.state.set_system_language(language);
+    }
+
+    /// The layout of the last frame
+    pub fn form_factor(&self) -> FormFactor {
+        self.form_factor
+    }
+
+    /// Uses `form_factor` regardless of the window and the settings, `None` stops that
+    pub fn force_form_factor(&mut self, form_factor: Option<FormFactor>) {
+        self.forced_form_factor = form_factor;
+    }
+
+    /// Makes the UI treat `today` as the current date, for reproducible screenshots
+    pub fn pin_today(&mut self, today: NaiveDate) {
+        self.pinned_today = Some(today);
+        self.state.apply(&Message::DayChanged(today));
+    }
+
+    /// Opens the screen named `name`, returns `false` if no screen has that name
+    pub fn navigate_to(&mut self, name: &str) -> bool {
+        match self.screens.get_mut(name) {
+            Some(screen) => {
+                self.navigation.navigate_to(screen.name());
+                true
+            }
+            None => {
+                log::warn!("No screen named {} is registered", name);
+                false
+            }
+        }
+    }
+
+    /// The names of the registered screens, in the order of the navigation
+    pub fn screen_names(&self) -> Vec<&'static str> {
+        self.screens
+            .entries()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// Handles the back button, returns `false` if there is no screen to go back to
+    pub fn back(&mut self) -> bool {
+        self.navigation.back()
+    }
+
+    /// Whether the user asked to quit, the event loop should exit after a `flush`
+    pub fn is_quit_requested(&self) -> bool {
+        self.is_quit_requested
+    }
+
+    /// Writes everything to the storage
+    pub fn flush(&self) {
+        if let Err(err) = self.state.save_all(&*self.storage) {
+            log::error!("Failed to save: {}", err);
         }
+
+        self.storage.flush();
+    }
+
+    fn today(&self) -> NaiveDate {
+        self.pinned_today
+            .unwrap_or_else(|| Local::now().date_naive())
+    }
+
+    fn apply(&mut self, message: Message) {
+        self.state.apply(&message);
+
+        if let Err(err) = self.state.save(&*self.storage, &message) {
+            self.save_error = Some(
+                self.state
+                    .locale()
+                    .text_with("save-error", &[("error", err.to_string().into())]),
+            );
+        }
+    }
+
+    /// Handles undo and redo, after the screens so a focused text field keeps its own undo
+    fn undo_shortcuts(&mut self, ctx: &Context) {
+        if ctx.output(|output| output.text_cursor_pos.is_some()) {
+            return;
+        }
+
+        // Ctrl+Shift+Z would match Ctrl+Z otherwise
+        if ctx.input_mut(|input| {
+            input.consume_shortcut(&shortcuts::REDO_ALTERNATIVE)
+                || input.consume_shortcut(&shortcuts::REDO)
+        }) {
+            self.redo();
+        } else if ctx.input_mut(|input| input.consume_shortcut(&shortcuts::UNDO)) {
+            self.undo();
+        }
+    }
+
+    /// Follows the input type and picks the layout for the frame
+    fn update_form_factor(&mut self, ctx: &Context) {
+        ctx.input(|input| {
+            let has_touch = input
+                .events
+                .iter()
+                .any(|event| matches!(event, egui::Event::Touch { .. }));
+            let has_pointer_move = input
+                .events
+                .iter()
+                .any(|event| matches!(event, egui::Event::PointerMoved(_)));
+
+            // Touches move the pointer too, only moves without a touch come from a mouse
+            if has_touch {
+                self.is_touch = true;
+            } else if has_pointer_move {
+                self.is_touch = false;
+            }
+        });
+
+        let logical_size = ctx.input(|i| i.screen_rect.size());
+
+        self.form_factor = self
+            .forced_form_factor
+            .or(self.state.settings().form_factor)
+            .unwrap_or_else(|| FormFactor::detect(logical_size, self.is_touch));
     }
 
     fn mobile_ui(&mut self, ctx: &Context) {
-        // let screen_size = ctx.input(|i| i.screen_rect.size());
-        // let default_width = (screen_size.x - 20.0).min(400.0);
+        self.save_error_bar(ctx);
+        self.bottom_bar(ctx);
+        self.update_visible_screen();
 
-        self.show_main_gui(ctx);
+        let screen = self.screens.get_mut(self.navigation.current()).unwrap();
+        let (state, bus) = (&self.state, &mut self.bus);
+
+        CentralPanel::default().show(ctx, |ui| {
+            ui.vertical_centered_justified(|ui| screen.ui(ui, state, bus));
+        });
         // self.show_about(ctx);
     }
 
     fn desktop_ui(&mut self, ctx: &Context) {
-        self.show_main_gui(ctx);
+        self.save_error_bar(ctx);
+        self.side_panel(ctx);
+        self.update_visible_screen();
+
+        let screen = self.screens.get_mut(self.navigation.current()).unwrap();
+        let (state, bus) = (&self.state, &mut self.bus);
+
+        CentralPanel::default().show(ctx, |ui| screen.desktop_ui(ui, state, bus));
         // self.show_about(ctx);
     }
 
@@ -94,49 +338,144 @@ impl MainWindows {
         self.about.show(ctx, &mut self.is_about_open);
     }
 
+    fn navigation_button_height(ctx: &Context, lines: usize, min_height: f32) -> f32 {
+        let style = ctx.style();
+        let font_id = TextStyle::Name("NavigationButton".into()).resolve(&style);
+        let text_height = ctx.fonts(|fonts| fonts.row_height(&font_id)) * lines as f32;
+
+        (text_height + 4.0 * style.spacing.button_padding.y).max(min_height)
+    }
+
     fn bottom_bar(&mut self, ctx: &Context) {
-        let screen_size = ctx.input(|i| i.screen_rect.size());
-        let bottom_panel_height = screen_size.y / 10.0;
-        let button_width = screen_size.x / 3.0 - 10.0;
+        // Touch targets on phones should be at least 48 points high
+        // The icon goes above the name, the columns are too narrow for both in one line
+        let button_height = Self::navigation_button_height(ctx, 2, 48.0);
 
-        let bottom_panel =
-            egui::TopBottomPanel::bottom(Id::new("bottom_bar")).exact_height(bottom_panel_height);
+        let bottom_panel = egui::TopBottomPanel::bottom(Id::new("bottom_bar"));
+
+        let entries = self.screens.entries();
 
         bottom_panel.show(ctx, |ui| {
-            ui.horizontal_top(|ui| {
-                let home_button = Button::new(RichText::new("Home").size(40.0));
+            ui.columns(entries.len() + 1, |columns| {
+                for (column, (name, icon)) in columns.iter_mut().zip(&entries) {
+                    let label = self.screen_label(name);
+                    let text = format!("{}\n{}", icon, label);
+                    self.navigation_button(column, name, &label, text, button_height);
+                }
 
-                ui.add_sized([button_width, bottom_panel_height / 2.0], home_button)
-                    .clicked()
-                    .then(|| {
-                        self.main_window_state = MainWindowState::Main;
-                    });
+                self.quit_button(&mut columns[entries.len()], button_height);
+            });
+        });
+    }
+
+    fn side_panel(&mut self, ctx: &Context) {
+        let button_height = Self::navigation_button_height(ctx, 1, 32.0);
 
-                let dates_button = Button::new(RichText::new("Dates").size(40.0));
+        let side_panel = egui::SidePanel::left(Id::new("side_panel")).resizable(false);
 
-                ui.add_sized([button_width, bottom_panel_height / 2.0], dates_button)
+        side_panel.show(ctx, |ui| {
+            ui.vertical_centered_justified(|ui| {
+                for (name, icon) in self.screens.entries() {
+                    let label = self.screen_label(name);
+                    let text = format!("{} {}", icon, label);
+                    self.navigation_button(ui, name, &label, text, button_height);
+                }
+
+                ui.separator();
+
+                let shortcuts_text = format!("⌨ {}", self.state.locale().text("shortcuts"));
+                let shortcuts_button = Button::new(navigation_text(&shortcuts_text));
+
+                ui.add_sized([ui.available_width(), button_height], shortcuts_button)
                     .clicked()
                     .then(|| {
-                        self.main_window_state = MainWindowState::Dates;
+                        self.is_shortcuts_open = !self.is_shortcuts_open;
                     });
 
-                let quit_button = Button::new(RichText::new("Quit").size(40.0));
-
-                ui.add_sized([button_width, bottom_panel_height / 2.0], quit_button)
-                    .clicked()
-                    .then(|| std::process::exit(0));
+                self.quit_button(ui, button_height);
             });
         });
     }
 
-    fn show_main_gui(&mut self, ctx: &Context) {
-        self.bottom_bar(ctx);
+    /// The translated name of a screen, screens without one in the catalogs keep their name
+    fn screen_label(&self, name: &str) -> String {
+        self.state
+            .locale()
+            .try_text(&format!("screen-{}", name.to_lowercase()))
+            .unwrap_or_else(|| name.to_owned())
+    }
 
-        CentralPanel::default().show(ctx, |ui| {
-            ui.vertical_centered_justified(|ui| match self.main_window_state {
-                MainWindowState::Main => self.home.ui(ui),
-                MainWindowState::Dates => self.dates.ui(ui),
+    fn navigation_button(
+        &mut self,
+        ui: &mut egui::Ui,
+        name: &'static str,
+        label: &str,
+        text: String,
+        height: f32,
+    ) {
+        let button =
+            SelectableLabel::new(self.navigation.current() == name, navigation_text(&text));
+
+        let response = ui.add_sized([ui.available_width(), height], button);
+        set_accessible_name(&response, label);
+
+        response.clicked().then(|| {
+            self.navigation.navigate_to(name);
+        });
+    }
+
+    fn quit_button(&mut self, ui: &mut egui::Ui, height: f32) {
+        let quit_button = Button::new(navigation_text(&self.state.locale().text("quit")));
+
+        ui.add_sized([ui.available_width(), height], quit_button)
+            .clicked()
+            .then(|| {
+                self.is_quit_requested = true;
+            });
+    }
+
+    fn save_error_bar(&mut self, ctx: &Context) {
+        let dismiss_text = self.state.locale().text("dismiss");
+        let error = match &self.save_error {
+            Some(error) => error,
+            None => return,
+        };
+
+        let mut is_dismissed = false;
+
+        egui::TopBottomPanel::top(Id::new("save_error")).show(ctx, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+                is_dismissed = ui.button(dismiss_text).clicked();
             });
         });
+
+        if is_dismissed {
+            self.save_error = None;
+        }
     }
+
+    /// Calls the lifecycle hooks if the navigation opened another screen
+    fn update_visible_screen(&mut self) {
+        let current = self.navigation.current();
+
+        if self.visible_screen == Some(current) {
+            return;
+        }
+
+        if let Some(previous) = self.visible_screen {
+            self.screens
+                .get_mut(previous)
+                .unwrap()
+                .on_leave(&self.state);
+        }
+
+        self.screens.get_mut(current).unwrap().on_enter(&self.state);
+
+        self.visible_screen = Some(current);
+    }
+}
+
+fn navigation_text(text: &str) -> RichText {
+    RichText::new(text).text_style(TextStyle::Name("NavigationButton".into()))
 }
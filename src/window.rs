@@ -0,0 +1,166 @@
+use std::io;
+
+use winit::{
+    dpi::{LogicalSize, PhysicalPosition},
+    event_loop::EventLoopWindowTarget,
+    window::{Icon, Window, WindowBuilder},
+};
+
+use crate::{
+    io::{read_window_geometry, write_window_geometry},
+    storage::Storage,
+};
+
+pub const APP_TITLE: &str = "Android Rust Experiments";
+
+/// A typical phone in portrait, in logical pixels
+pub const PHONE_SIZE: [u32; 2] = [412, 892];
+
+const ICON: &[u8] = include_bytes!("../assets/icon.png");
+
+/// Where and how big the desktop window was, restored on the next start
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WindowGeometry {
+    /// Outer position in physical pixels, `None` lets the system place the window
+    pub position: Option<[i32; 2]>,
+    /// Inner size in logical pixels, the last one the window had when it wasn't maximized
+    pub size: [u32; 2],
+    pub maximized: bool,
+}
+
+impl WindowGeometry {
+    /// The geometry of the last run
+    pub fn load(storage: &dyn Storage) -> Option<Self> {
+        read_window_geometry(storage)
+    }
+
+    pub fn save(&self, storage: &dyn Storage) -> io::Result<()> {
+        write_window_geometry(storage, self)
+    }
+
+    /// Follows moves and resizes of `window`
+    pub(crate) fn update(&mut self, window: &Window) {
+        self.maximized = window.is_maximized();
+
+        // Restoring a maximized window should bring back its old size
+        if self.maximized {
+            return;
+        }
+
+        if let Ok(position) = window.outer_position() {
+            self.position = Some([position.x, position.y]);
+        }
+
+        let size = window.inner_size().to_logical::<u32>(window.scale_factor());
+        self.size = [size.width, size.height];
+    }
+}
+
+/// How the desktop window is opened, ignored on Android
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WindowOptions {
+    pub title: String,
+    /// Inner size in logical pixels, when there is no saved geometry
+    pub size: [u32; 2],
+    pub min_size: [u32; 2],
+    /// Opens a fixed window of `PHONE_SIZE`, to try the compact layout
+    pub phone_preview: bool,
+}
+
+impl Default for WindowOptions {
+    fn default() -> Self {
+        Self {
+            title: APP_TITLE.to_owned(),
+            size: [1024, 720],
+            min_size: [360, 480],
+            phone_preview: false,
+        }
+    }
+}
+
+impl WindowOptions {
+    /// Where the window opens, `saved` is the geometry of the last run
+    pub fn initial_geometry(&self, saved: Option<WindowGeometry>) -> WindowGeometry {
+        if self.phone_preview {
+            return WindowGeometry {
+                position: saved.and_then(|saved| saved.position),
+                size: PHONE_SIZE,
+                maximized: false,
+            };
+        }
+
+        match saved {
+            Some(saved) => WindowGeometry {
+                size: [
+                    saved.size[0].max(self.min_size[0]),
+                    saved.size[1].max(self.min_size[1]),
+                ],
+                ..saved
+            },
+            None => WindowGeometry {
+                position: None,
+                size: self.size,
+                maximized: false,
+            },
+        }
+    }
+
+    pub(crate) fn window_builder(&self, geometry: WindowGeometry) -> WindowBuilder {
+        let [width, height] = geometry.size;
+
+        let mut builder = WindowBuilder::new()
+            .with_inner_size(LogicalSize::new(width, height))
+            .with_maximized(geometry.maximized)
+            .with_window_icon(icon());
+
+        if let Some([x, y]) = geometry.position {
+            builder = builder.with_position(PhysicalPosition::new(x, y));
+        }
+
+        if self.phone_preview {
+            builder
+                .with_title(format!("{} (phone preview)", self.title))
+                .with_resizable(false)
+        } else {
+            let [min_width, min_height] = self.min_size;
+
+            builder
+                .with_title(&self.title)
+                .with_resizable(true)
+                .with_min_inner_size(LogicalSize::new(min_width, min_height))
+        }
+    }
+}
+
+/// Drops the position if it is on no monitor anymore, e.g. after one was unplugged
+pub(crate) fn on_a_monitor<T>(
+    mut geometry: WindowGeometry,
+    event_loop: &EventLoopWindowTarget<T>,
+) -> WindowGeometry {
+    if let Some([x, y]) = geometry.position {
+        let is_visible = event_loop.available_monitors().any(|monitor| {
+            let position = monitor.position();
+            let size = monitor.size();
+
+            (position.x..position.x + size.width as i32).contains(&x)
+                && (position.y..position.y + size.height as i32).contains(&y)
+        });
+
+        if !is_visible {
+            geometry.position = None;
+        }
+    }
+
+    geometry
+}
+
+fn icon() -> Option<Icon> {
+    let decoder = png::Decoder::new(ICON);
+    let mut reader = decoder.read_info().ok()?;
+
+    let mut data = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut data).ok()?;
+    data.truncate(info.buffer_size());
+
+    Icon::from_rgba(data, info.width, info.height).ok()
+}
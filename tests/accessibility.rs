@@ -0,0 +1,332 @@
+use std::collections::BTreeMap;
+
+use android_rust_experiments::{
+    AppState, Dates, Event, Goals, MessageBus, RemindersView, Settings, StateView, VirtualView,
+    VirtualViewAction, VirtualViews,
+};
+use chrono::{Datelike, Local, NaiveDate};
+use egui::accesskit::{Action, ActionRequest, Node, NodeId, Role, TreeUpdate};
+
+/// Roles a user can focus and operate
+const INTERACTIVE_ROLES: [Role; 10] = [
+    Role::Button,
+    Role::CheckBox,
+    Role::ColorWell,
+    Role::Link,
+    Role::PopupButton,
+    Role::RadioButton,
+    Role::Slider,
+    Role::SpinButton,
+    Role::TextField,
+    Role::ToggleButton,
+];
+
+struct Views {
+    state: AppState,
+    bus: MessageBus,
+    dates: Dates,
+    goals: Goals,
+    reminders: RemindersView,
+    settings: Settings,
+}
+
+impl Views {
+    fn new() -> Self {
+        let dates = vec![
+            Event::new(NaiveDate::from_ymd_opt(2023, 4, 1).unwrap()),
+            Event::new(NaiveDate::from_ymd_opt(2023, 5, 2).unwrap()),
+        ];
+
+        Self {
+            state: AppState::new(
+                dates,
+                Vec::new(),
+                BTreeMap::new(),
+                Settings::default(),
+                Local::now().date_naive(),
+            ),
+            bus: MessageBus::default(),
+            dates: Dates::default(),
+            goals: Goals::default(),
+            reminders: RemindersView,
+            settings: Settings::default(),
+        }
+    }
+}
+
+fn context() -> egui::Context {
+    let ctx = egui::Context::default();
+    ctx.enable_accesskit();
+
+    // `Dates::ui` relies on the text styles that `MainWindows` registers
+    let mut style = (*ctx.style()).clone();
+    style.text_styles.insert(
+        egui::TextStyle::Name("DateInputButton".into()),
+        egui::FontId::proportional(28.0),
+    );
+    ctx.set_style(style);
+
+    ctx
+}
+
+/// Runs one frame with `events` and returns the AccessKit tree it produced
+fn run(ctx: &egui::Context, views: &mut Views, events: Vec<egui::Event>) -> TreeUpdate {
+    let raw_input = egui::RawInput {
+        screen_rect: Some(egui::Rect::from_min_size(
+            egui::Pos2::ZERO,
+            egui::vec2(400.0, 2000.0),
+        )),
+        events,
+        ..Default::default()
+    };
+
+    let full_output = ctx.run(raw_input, |ctx| {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            let (state, bus) = (&views.state, &mut views.bus);
+
+            views.dates.ui(ui, state, bus);
+            views.goals.ui(ui, state, bus);
+            views.reminders.ui(ui, state, bus);
+            views
+                .settings
+                .ui(ui, state.locale(), state.system_language());
+        });
+    });
+
+    for message in views.bus.take() {
+        views.state.apply(&message);
+    }
+
+    full_output.platform_output.accesskit_update.unwrap()
+}
+
+/// Icons like "x" or "+" do not count as a name
+fn is_meaningful(name: &str) -> bool {
+    name.chars().filter(|c| c.is_alphabetic()).count() >= 2
+}
+
+fn find_by_name<'a>(tree: &'a TreeUpdate, name: &str) -> Option<(NodeId, &'a Node)> {
+    tree.nodes
+        .iter()
+        .find(|(_, node)| node.name() == Some(name))
+        .map(|(id, node)| (*id, node))
+}
+
+fn focused_name(tree: &TreeUpdate) -> Option<&str> {
+    let focus = tree.focus?;
+
+    tree.nodes
+        .iter()
+        .find(|(id, _)| *id == focus)
+        .and_then(|(_, node)| node.name())
+}
+
+fn action(target: NodeId, action: Action) -> egui::Event {
+    egui::Event::AccessKitActionRequest(ActionRequest {
+        action,
+        target,
+        data: None,
+    })
+}
+
+fn key(key: egui::Key) -> egui::Event {
+    egui::Event::Key {
+        key,
+        pressed: true,
+        repeat: false,
+        modifiers: egui::Modifiers::NONE,
+    }
+}
+
+#[test]
+fn every_interactive_node_is_labelled() {
+    let ctx = context();
+    let mut views = Views::new();
+
+    let tree = run(&ctx, &mut views, Vec::new());
+
+    // Open the collapsed sections like a screen reader user would
+    let expand = ["Goals", "Reminders: default"]
+        .iter()
+        .map(|name| action(find_by_name(&tree, name).unwrap().0, Action::Default))
+        .collect();
+    run(&ctx, &mut views, expand);
+
+    let tree = run(&ctx, &mut views, Vec::new());
+
+    assert!(find_by_name(&tree, "Target").is_some());
+    assert!(find_by_name(&tree, "Before a streak lapses").is_some());
+
+    let unlabelled: Vec<_> = tree
+        .nodes
+        .iter()
+        .filter(|(_, node)| INTERACTIVE_ROLES.contains(&node.role()))
+        .filter(|(_, node)| !is_meaningful(node.name().unwrap_or_default()))
+        .map(|(_, node)| (node.role(), node.name()))
+        .collect();
+
+    assert!(unlabelled.is_empty(), "unlabelled nodes: {:?}", unlabelled);
+}
+
+#[test]
+fn delete_buttons_name_their_date() {
+    let ctx = context();
+    let mut views = Views::new();
+
+    let tree = run(&ctx, &mut views, Vec::new());
+
+    let (_, node) = find_by_name(&tree, "Delete event Apr 1, 2023").unwrap();
+    assert_eq!(node.role(), Role::Button);
+    assert!(find_by_name(&tree, "Delete event May 2, 2023").is_some());
+}
+
+#[test]
+fn deleting_a_row_removes_the_event_of_that_row() {
+    let ctx = context();
+    let mut views = Views::new();
+
+    let day = NaiveDate::from_ymd_opt(2023, 4, 1).unwrap();
+    views.state = AppState::new(
+        vec![Event::of_tracker(day, "gym"), Event::new(day)],
+        Vec::new(),
+        BTreeMap::new(),
+        Settings {
+            confirm_delete: false,
+            ..Settings::default()
+        },
+        Local::now().date_naive(),
+    );
+
+    let tree = run(&ctx, &mut views, Vec::new());
+
+    // The first row is the gym event
+    let (delete, _) = find_by_name(&tree, "Delete event Apr 1, 2023").unwrap();
+    run(&ctx, &mut views, vec![action(delete, Action::Default)]);
+
+    let events: Vec<&Event> = views.state.events().iter().collect();
+    assert_eq!(events, [&Event::new(day)]);
+}
+
+#[test]
+fn tab_moves_through_the_dates_tab_in_order() {
+    let ctx = context();
+    let mut views = Views::new();
+
+    run(&ctx, &mut views, Vec::new());
+
+    let expected = [
+        "Delete event Apr 1, 2023",
+        "Delete event May 2, 2023",
+        "Year",
+        "Month",
+        "Day",
+    ];
+
+    for name in expected {
+        let tree = run(&ctx, &mut views, vec![key(egui::Key::Tab)]);
+        assert_eq!(focused_name(&tree), Some(name));
+    }
+}
+
+#[test]
+fn arrow_keys_step_the_focused_picker() {
+    let ctx = context();
+    let mut views = Views::new();
+
+    let tree = run(&ctx, &mut views, Vec::new());
+    let (year, _) = find_by_name(&tree, "Year").unwrap();
+
+    run(&ctx, &mut views, vec![action(year, Action::Focus)]);
+    run(&ctx, &mut views, vec![key(egui::Key::ArrowDown)]);
+
+    let tree = run(&ctx, &mut views, Vec::new());
+
+    let today = Local::now().date_naive();
+    // The 29th of February becomes the 28th a year ago
+    let date = NaiveDate::from_ymd_opt(today.year() - 1, today.month(), today.day())
+        .or_else(|| NaiveDate::from_ymd_opt(today.year() - 1, 2, 28))
+        .unwrap();
+    let expected = format!("Add event {}", date.format("%b %-d, %Y"));
+
+    assert!(find_by_name(&tree, &expected).is_some());
+}
+
+fn view_by_label<'a>(views: &'a VirtualViews, label: &str) -> &'a VirtualView {
+    views
+        .views()
+        .iter()
+        .find(|view| view.label == label)
+        .unwrap_or_else(|| panic!("no view labelled {:?}", label))
+}
+
+#[test]
+fn virtual_views_mirror_the_tree() {
+    let ctx = context();
+    let mut views = Views::new();
+    let mut virtual_views = VirtualViews::default();
+
+    let tree = run(&ctx, &mut views, Vec::new());
+    virtual_views.update(&tree, 2.0);
+
+    let (_, node) = find_by_name(&tree, "Delete event Apr 1, 2023").unwrap();
+    let bounds = node.bounds().unwrap();
+    let view = view_by_label(&virtual_views, "Delete event Apr 1, 2023");
+
+    assert_eq!(view.class_name, "android.widget.Button");
+    assert!(view.is_clickable);
+    assert!(view.is_focusable);
+    assert_eq!(
+        view.bounds,
+        [
+            (bounds.x0 * 2.0).round() as i32,
+            (bounds.y0 * 2.0).round() as i32,
+            (bounds.x1 * 2.0).round() as i32,
+            (bounds.y1 * 2.0).round() as i32,
+        ]
+    );
+
+    // Parents come before their children, which TalkBack needs to build the hierarchy
+    for (index, view) in virtual_views.views().iter().enumerate() {
+        if let Some(parent) = view.parent {
+            assert!(virtual_views.views()[..index]
+                .iter()
+                .any(|earlier| earlier.id == parent));
+        }
+    }
+
+    let id = view.id;
+    let tree = run(&ctx, &mut views, Vec::new());
+    virtual_views.update(&tree, 2.0);
+
+    assert_eq!(
+        view_by_label(&virtual_views, "Delete event Apr 1, 2023").id,
+        id
+    );
+}
+
+#[test]
+fn clicking_a_virtual_view_activates_its_widget() {
+    let ctx = context();
+    let mut views = Views::new();
+    let mut virtual_views = VirtualViews::default();
+
+    let tree = run(&ctx, &mut views, Vec::new());
+    virtual_views.update(&tree, 1.0);
+    assert!(find_by_name(&tree, "Target").is_none());
+
+    let goals = view_by_label(&virtual_views, "Goals").id;
+    let request = virtual_views
+        .action_request(goals, VirtualViewAction::Click)
+        .unwrap();
+    run(
+        &ctx,
+        &mut views,
+        vec![egui::Event::AccessKitActionRequest(request)],
+    );
+
+    let tree = run(&ctx, &mut views, Vec::new());
+    assert!(find_by_name(&tree, "Target").is_some());
+    assert!(virtual_views
+        .action_request(i32::MAX, VirtualViewAction::Focus)
+        .is_none());
+}
@@ -0,0 +1,191 @@
+use android_rust_experiments::DatePicker;
+use chrono::NaiveDate;
+use egui::{
+    accesskit::{Action, ActionRequest, TreeUpdate},
+    Event, Key, Modifiers, PointerButton, Pos2, RawInput, Rect, Vec2,
+};
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+/// A `DatePicker` alone on a screen
+struct Picker {
+    ctx: egui::Context,
+    date: NaiveDate,
+    min: NaiveDate,
+    max: NaiveDate,
+    tree: TreeUpdate,
+}
+
+impl Picker {
+    fn new(date: NaiveDate) -> Self {
+        let ctx = egui::Context::default();
+        ctx.enable_accesskit();
+
+        let mut picker = Self {
+            ctx,
+            date,
+            min: NaiveDate::MIN,
+            max: NaiveDate::MAX,
+            tree: TreeUpdate::default(),
+        };
+        picker.run(Vec::new());
+
+        picker
+    }
+
+    fn run(&mut self, events: Vec<Event>) {
+        let raw_input = RawInput {
+            screen_rect: Some(Rect::from_min_size(Pos2::ZERO, egui::vec2(400.0, 800.0))),
+            events,
+            ..Default::default()
+        };
+
+        let (date, min, max) = (&mut self.date, self.min, self.max);
+        let full_output = self.ctx.run(raw_input, |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                ui.add(DatePicker::new("picker", date).min(min).max(max));
+            });
+        });
+
+        self.tree = full_output.platform_output.accesskit_update.unwrap();
+    }
+
+    fn node_rect(&self, name: &str) -> Option<Rect> {
+        let (_, node) = self
+            .tree
+            .nodes
+            .iter()
+            .find(|(_, node)| node.name() == Some(name))?;
+        let bounds = node.bounds().unwrap();
+
+        Some(Rect::from_min_max(
+            Pos2::new(bounds.x0 as f32, bounds.y0 as f32),
+            Pos2::new(bounds.x1 as f32, bounds.y1 as f32),
+        ))
+    }
+
+    /// Sends a screen reader action to the widget named `name`
+    fn action(&mut self, name: &str, action: Action) {
+        let (target, _) = self
+            .tree
+            .nodes
+            .iter()
+            .find(|(_, node)| node.name() == Some(name))
+            .unwrap();
+
+        let request = ActionRequest {
+            action,
+            target: *target,
+            data: None,
+        };
+        self.run(vec![Event::AccessKitActionRequest(request)]);
+    }
+
+    fn tap(&mut self, name: &str) {
+        let position = self.node_rect(name).unwrap().center();
+
+        self.run(vec![Event::PointerMoved(position)]);
+        self.run(vec![button(position, true)]);
+        self.run(vec![button(position, false)]);
+        self.run(Vec::new());
+    }
+
+    /// Drags by `step` in each of `steps` frames and lets go
+    fn flick(&mut self, name: &str, step: Vec2, steps: usize) {
+        let mut position = self.node_rect(name).unwrap().center();
+
+        self.run(vec![Event::PointerMoved(position)]);
+        self.run(vec![button(position, true)]);
+
+        for _ in 0..steps {
+            position += step;
+            self.run(vec![Event::PointerMoved(position)]);
+        }
+
+        self.run(vec![button(position, false)]);
+    }
+}
+
+fn button(pos: Pos2, pressed: bool) -> Event {
+    Event::PointerButton {
+        pos,
+        button: PointerButton::Primary,
+        pressed,
+        modifiers: Modifiers::NONE,
+    }
+}
+
+#[test]
+fn the_days_follow_the_month() {
+    let mut picker = Picker::new(date(2024, 1, 31));
+
+    picker.action("Month", Action::Focus);
+    picker.run(vec![Event::Key {
+        key: Key::ArrowUp,
+        pressed: true,
+        repeat: false,
+        modifiers: Modifiers::NONE,
+    }]);
+    assert_eq!(picker.date, date(2024, 2, 29));
+
+    picker.action("Year", Action::Decrement);
+    assert_eq!(picker.date, date(2023, 2, 28));
+}
+
+#[test]
+fn the_wheels_stop_at_min_and_max() {
+    let mut picker = Picker::new(date(2023, 6, 20));
+    picker.min = date(2023, 3, 10);
+    picker.max = date(2023, 6, 20);
+    picker.run(Vec::new());
+
+    picker.action("Year", Action::Increment);
+    picker.action("Day", Action::Increment);
+    assert_eq!(picker.date, date(2023, 6, 20));
+
+    for _ in 0..5 {
+        picker.action("Month", Action::Decrement);
+    }
+    assert_eq!(picker.date, date(2023, 3, 20));
+
+    for _ in 0..15 {
+        picker.action("Day", Action::Decrement);
+    }
+    assert_eq!(picker.date, date(2023, 3, 10));
+}
+
+#[test]
+fn a_flicked_wheel_keeps_turning_after_letting_go() {
+    let mut picker = Picker::new(date(2023, 6, 1));
+
+    picker.flick("Day", egui::vec2(0.0, -20.0), 3);
+    let released = picker.date;
+    assert!(released > date(2023, 6, 1));
+
+    for _ in 0..120 {
+        picker.run(Vec::new());
+    }
+
+    assert!(picker.date > released);
+    assert!(picker.date <= date(2023, 6, 30));
+}
+
+#[test]
+fn the_calendar_picks_a_day() {
+    let mut picker = Picker::new(date(2023, 6, 15));
+
+    picker.tap("Calendar");
+
+    // The popup moves back onto the screen while its grid finds its size
+    for _ in 0..3 {
+        picker.run(Vec::new());
+    }
+
+    picker.tap("Next month");
+    picker.tap("Monday, July 10, 2023");
+
+    assert_eq!(picker.date, date(2023, 7, 10));
+    assert!(picker.node_rect("Next month").is_none());
+}
@@ -0,0 +1,190 @@
+use android_rust_experiments::{Event, Events};
+use chrono::NaiveDate;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn dates_from(events: &Events, index: usize) -> Vec<NaiveDate> {
+    events.iter_from(index).map(|event| event.date).collect()
+}
+
+/// Every suffix of the sorted `dates`, as `iter_from` should return it
+fn assert_iter_from(events: &Events, dates: &[NaiveDate]) {
+    let mut dates = dates.to_vec();
+    dates.sort();
+
+    assert_eq!(events.len(), dates.len());
+    for index in 0..=dates.len() + 1 {
+        assert_eq!(
+            dates_from(events, index),
+            dates.get(index..).unwrap_or_default(),
+            "from index {}",
+            index
+        );
+    }
+}
+
+#[test]
+fn iter_from_starts_inside_a_day() {
+    let dates = [
+        date(2023, 5, 2),
+        date(2023, 4, 1),
+        date(2023, 5, 2),
+        date(2023, 6, 30),
+        date(2023, 4, 1),
+        date(2023, 5, 2),
+    ];
+    let events: Events = dates.iter().copied().map(Event::new).collect();
+
+    assert_iter_from(&events, &dates);
+    assert_eq!(
+        dates_from(&events, 3),
+        [date(2023, 5, 2), date(2023, 5, 2), date(2023, 6, 30)]
+    );
+    assert!(Events::default().iter_from(0).next().is_none());
+
+    assert_eq!(events.index_of(date(2023, 5, 2)), 2);
+    assert_eq!(events.index_of(date(2023, 5, 3)), 5);
+    assert_eq!(events.index_of(date(2000, 1, 1)), 0);
+    assert_eq!(events.index_of(date(2100, 1, 1)), 6);
+}
+
+#[test]
+fn inserting_and_removing_keeps_the_positions() {
+    let mut events = Events::default();
+    let mut dates = Vec::new();
+
+    // Days added in front of, between and after the others
+    for date in [
+        date(2023, 5, 2),
+        date(2023, 6, 30),
+        date(2023, 4, 1),
+        date(2023, 5, 20),
+        date(2023, 5, 2),
+        date(2023, 7, 1),
+    ]
+    .iter()
+    .copied()
+    {
+        events.insert(Event::new(date));
+        dates.push(date);
+        assert_iter_from(&events, &dates);
+    }
+
+    // One of two events on a day, then whole days
+    for date in [
+        date(2023, 5, 2),
+        date(2023, 4, 1),
+        date(2023, 7, 1),
+        date(2023, 5, 2),
+    ]
+    .iter()
+    .copied()
+    {
+        assert_eq!(events.remove(&Event::new(date)), Some(Event::new(date)));
+        let position = dates.iter().position(|other| *other == date).unwrap();
+        dates.remove(position);
+        assert_iter_from(&events, &dates);
+    }
+
+    assert_eq!(events.remove(&Event::new(date(2023, 4, 1))), None);
+    assert_eq!(events.len(), 2);
+    assert!(!events.is_empty());
+}
+
+#[test]
+fn days_far_apart_keep_the_positions() {
+    let mut events = Events::default();
+    let mut dates = Vec::new();
+
+    // The covered days grow into the past and into the future
+    for date in [
+        date(2023, 4, 1),
+        date(1990, 1, 1),
+        date(2050, 12, 31),
+        date(2023, 4, 1),
+        date(1970, 6, 15),
+        date(2100, 2, 28),
+    ]
+    .iter()
+    .copied()
+    {
+        events.insert(Event::new(date));
+        dates.push(date);
+        assert_iter_from(&events, &dates);
+    }
+
+    assert_eq!(
+        events.remove(&Event::new(date(1970, 6, 15))),
+        Some(Event::new(date(1970, 6, 15)))
+    );
+    dates.remove(4);
+    assert_iter_from(&events, &dates);
+}
+
+#[test]
+fn len_counts_every_event() {
+    let mut events = Events::default();
+    assert!(events.is_empty());
+
+    events.insert(Event::new(date(2023, 4, 1)));
+    events.insert(Event::new(date(2023, 4, 1)));
+    events.insert(Event::new(date(2023, 4, 2)));
+    assert_eq!(events.len(), 3);
+    assert_eq!(events.count(date(2023, 4, 1)..=date(2023, 4, 1)), 2);
+
+    events.remove(&Event::new(date(2023, 4, 1)));
+    events.remove(&Event::new(date(2023, 4, 2)));
+    events.remove(&Event::new(date(2023, 4, 2)));
+    assert_eq!(events.len(), 1);
+}
+
+#[test]
+fn remove_keeps_the_events_of_other_trackers() {
+    let day = date(2023, 4, 1);
+    let mut events: Events = vec![
+        Event::of_tracker(day, "gym"),
+        Event::new(day),
+        Event::new(date(2023, 4, 2)),
+    ]
+    .into_iter()
+    .collect();
+
+    assert_eq!(
+        events.remove(&Event::of_tracker(day, "gym")),
+        Some(Event::of_tracker(day, "gym"))
+    );
+    assert_eq!(events.remove(&Event::of_tracker(day, "gym")), None);
+
+    assert_eq!(events.last_on(day), Some(&Event::new(day)));
+    assert_eq!(
+        events.iter_from(1).next(),
+        Some(&Event::new(date(2023, 4, 2)))
+    );
+}
+
+#[test]
+fn every_tracker_is_counted_on_its_own() {
+    let mut events: Events = vec![
+        Event::of_tracker(date(2023, 4, 1), "gym"),
+        Event::of_tracker(date(2023, 4, 1), "gym"),
+        Event::new(date(2023, 4, 2)),
+        Event::of_tracker(date(2023, 4, 3), "gym"),
+    ]
+    .into_iter()
+    .collect();
+
+    assert_eq!(events.trackers().collect::<Vec<_>>(), ["default", "gym"]);
+    assert_eq!(events.count_of("gym", ..), 3);
+    assert_eq!(
+        events.last_date_of("gym", ..date(2023, 4, 3)),
+        Some(date(2023, 4, 1))
+    );
+
+    events.remove(&Event::new(date(2023, 4, 2)));
+
+    assert_eq!(events.trackers().collect::<Vec<_>>(), ["gym"]);
+    assert_eq!(events.count_of("default", ..), 0);
+    assert_eq!(events.last_date_of("default", ..), None);
+}
@@ -0,0 +1,182 @@
+use android_rust_experiments::{
+    AppState, Event, Events, Goal, GoalPeriod, MemoryStorage, PeriodResult, Storage,
+};
+use chrono::{NaiveDate, Weekday};
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn events(dates: &[NaiveDate]) -> Events {
+    dates.iter().copied().map(Event::new).collect()
+}
+
+fn goal(target: usize, period: GoalPeriod) -> Goal {
+    Goal { target, period }
+}
+
+#[test]
+fn weeks_start_on_the_week_start() {
+    // Sunday the 11th and Monday the 12th of June 2023
+    let sunday = date(2023, 6, 11);
+    let monday = date(2023, 6, 12);
+
+    assert_eq!(
+        GoalPeriod::Week.containing(sunday, Weekday::Mon),
+        date(2023, 6, 5)..monday
+    );
+    assert_eq!(
+        GoalPeriod::Week.containing(monday, Weekday::Mon),
+        monday..date(2023, 6, 19)
+    );
+    assert_eq!(
+        GoalPeriod::Week.containing(sunday, Weekday::Sun),
+        sunday..date(2023, 6, 18)
+    );
+    assert_eq!(
+        GoalPeriod::Week.containing(date(2023, 6, 9), Weekday::Sat),
+        date(2023, 6, 3)..date(2023, 6, 10)
+    );
+
+    // Across the end of a year
+    assert_eq!(
+        GoalPeriod::Week.containing(date(2024, 1, 1), Weekday::Sun),
+        date(2023, 12, 31)..date(2024, 1, 7)
+    );
+}
+
+#[test]
+fn months_and_years_end_on_their_last_day() {
+    assert_eq!(
+        GoalPeriod::Month.containing(date(2023, 1, 31), Weekday::Mon),
+        date(2023, 1, 1)..date(2023, 2, 1)
+    );
+    assert_eq!(
+        GoalPeriod::Month.containing(date(2023, 2, 1), Weekday::Mon),
+        date(2023, 2, 1)..date(2023, 3, 1)
+    );
+    assert_eq!(
+        GoalPeriod::Month.containing(date(2024, 2, 29), Weekday::Mon),
+        date(2024, 2, 1)..date(2024, 3, 1)
+    );
+    assert_eq!(
+        GoalPeriod::Month.containing(date(2023, 12, 31), Weekday::Mon),
+        date(2023, 12, 1)..date(2024, 1, 1)
+    );
+    assert_eq!(
+        GoalPeriod::Year.containing(date(2023, 12, 31), Weekday::Mon),
+        date(2023, 1, 1)..date(2024, 1, 1)
+    );
+    assert_eq!(
+        GoalPeriod::RollingYear.containing(date(2024, 2, 29), Weekday::Mon),
+        date(2023, 3, 1)..date(2024, 3, 1)
+    );
+}
+
+#[test]
+fn projections_scale_the_count_to_the_whole_period() {
+    let week = goal(3, GoalPeriod::Week);
+    let month = goal(3, GoalPeriod::Month);
+
+    // On the first day one event is a pace of one per day
+    let monday = date(2023, 6, 12);
+    assert_eq!(
+        week.status(&events(&[monday]), monday, Weekday::Mon)
+            .projected,
+        7
+    );
+    let first = date(2023, 6, 1);
+    assert_eq!(
+        month
+            .status(&events(&[first]), first, Weekday::Mon)
+            .projected,
+        30
+    );
+
+    // On the last day the projection is the count
+    let sunday = date(2023, 6, 18);
+    let status = week.status(&events(&[monday, sunday]), sunday, Weekday::Mon);
+    assert_eq!((status.count, status.projected), (2, 2));
+
+    let last = date(2023, 6, 30);
+    let status = month.status(
+        &events(&[first, date(2023, 6, 15), last]),
+        last,
+        Weekday::Mon,
+    );
+    assert_eq!((status.count, status.projected), (3, 3));
+
+    // Events of the previous period don't count
+    let status = month.status(&events(&[date(2023, 5, 31)]), first, Weekday::Mon);
+    assert_eq!((status.count, status.projected), (0, 0));
+
+    // A rolling year always covers its full length
+    let status = goal(3, GoalPeriod::RollingYear).status(
+        &events(&[date(2022, 6, 16), date(2023, 1, 1)]),
+        date(2023, 6, 15),
+        Weekday::Mon,
+    );
+    assert_eq!((status.count, status.projected), (2, 2));
+}
+
+#[test]
+fn history_lists_the_completed_periods() {
+    let events = events(&[
+        date(2023, 5, 1),
+        date(2023, 5, 31),
+        date(2023, 4, 15),
+        date(2023, 6, 1),
+    ]);
+
+    let status = goal(2, GoalPeriod::Month).status(&events, date(2023, 6, 15), Weekday::Mon);
+
+    assert_eq!(status.history.len(), 6);
+    assert_eq!(
+        status.history[..3],
+        [
+            PeriodResult {
+                period: date(2023, 5, 1)..date(2023, 6, 1),
+                count: 2,
+                is_met: true,
+            },
+            PeriodResult {
+                period: date(2023, 4, 1)..date(2023, 5, 1),
+                count: 1,
+                is_met: false,
+            },
+            PeriodResult {
+                period: date(2023, 3, 1)..date(2023, 4, 1),
+                count: 0,
+                is_met: false,
+            },
+        ]
+    );
+    assert_eq!(status.history[5].period.start, date(2022, 12, 1));
+
+    // Weeks follow the week start
+    let status = goal(1, GoalPeriod::Week).status(&events, date(2023, 6, 1), Weekday::Sun);
+    assert_eq!(
+        status.history[0].period,
+        date(2023, 5, 21)..date(2023, 5, 28)
+    );
+    assert_eq!(status.history[0].count, 0);
+    assert_eq!(
+        status.history[1].period,
+        date(2023, 5, 14)..date(2023, 5, 21)
+    );
+}
+
+#[test]
+fn malformed_goals_are_skipped() {
+    let storage = MemoryStorage::default();
+    storage
+        .write("goals.dat", "3 week\nthree month\n2\n1 decade\n\n5 year\n")
+        .unwrap();
+
+    let state = AppState::load(&storage, date(2023, 6, 15));
+
+    assert_eq!(
+        state.goals(),
+        [goal(3, GoalPeriod::Week), goal(5, GoalPeriod::Year)]
+    );
+}
@@ -0,0 +1,92 @@
+use android_rust_experiments::{
+    AppState, Backend, GraphicsCapabilities, GraphicsSettings, MemoryStorage, PowerPreference,
+    PresentMode, Storage,
+};
+use chrono::NaiveDate;
+
+fn capabilities() -> GraphicsCapabilities {
+    GraphicsCapabilities {
+        sample_counts: vec![1, 4],
+        present_modes: vec![wgpu::PresentMode::Fifo, wgpu::PresentMode::Mailbox],
+    }
+}
+
+#[test]
+fn supported_settings_are_kept() {
+    let settings = GraphicsSettings {
+        msaa_samples: 4,
+        present_mode: PresentMode::Mailbox,
+        power_preference: PowerPreference::HighPerformance,
+        backend: Backend::Vulkan,
+    };
+
+    assert_eq!(settings.supported_by(&capabilities()), settings);
+}
+
+#[test]
+fn unsupported_settings_fall_back() {
+    let settings = GraphicsSettings {
+        msaa_samples: 8,
+        present_mode: PresentMode::Immediate,
+        ..GraphicsSettings::default()
+    };
+
+    assert_eq!(
+        settings.supported_by(&capabilities()),
+        GraphicsSettings {
+            msaa_samples: 4,
+            present_mode: PresentMode::Vsync,
+            ..GraphicsSettings::default()
+        }
+    );
+
+    let settings = GraphicsSettings {
+        msaa_samples: 2,
+        ..GraphicsSettings::default()
+    };
+
+    assert_eq!(settings.supported_by(&capabilities()).msaa_samples, 1);
+}
+
+#[test]
+fn graphics_settings_are_read_from_the_settings_file() {
+    let storage = MemoryStorage::default();
+    storage
+        .write(
+            "settings.dat",
+            "theme dark\nmsaa 4\npresent_mode mailbox\npower_preference high\nbackend gl\n",
+        )
+        .unwrap();
+
+    let state = AppState::load(&storage, NaiveDate::from_ymd_opt(2023, 6, 15).unwrap());
+
+    assert_eq!(
+        state.settings().graphics,
+        GraphicsSettings {
+            msaa_samples: 4,
+            present_mode: PresentMode::Mailbox,
+            power_preference: PowerPreference::HighPerformance,
+            backend: Backend::Gl,
+        }
+    );
+}
+
+#[test]
+fn surface_failures_fall_back_to_opengl_once() {
+    let settings = GraphicsSettings {
+        msaa_samples: 4,
+        backend: Backend::Vulkan,
+        ..GraphicsSettings::default()
+    };
+
+    let fallback = settings.fallback().unwrap();
+
+    assert_eq!(
+        fallback,
+        GraphicsSettings {
+            backend: Backend::Gl,
+            ..settings
+        }
+    );
+    assert_eq!(fallback.fallback(), None);
+}
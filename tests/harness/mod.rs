@@ -0,0 +1,192 @@
+//! Drives `MainWindows` with synthetic input, without a window or GPU.
+//!
+//! Widgets are found by their accessible name in the AccessKit tree, which also gives their
+//! position for taps and drags.
+
+// Every test file uses only some of the helpers
+#![allow(dead_code)]
+
+use std::sync::Arc;
+
+use android_rust_experiments::{MainWindows, MemoryStorage, MockNotifications};
+use chrono::NaiveDate;
+use egui::{
+    accesskit::{Node, TreeUpdate},
+    Event, Key, Modifiers, PointerButton, Pos2, RawInput, Rect, Vec2,
+};
+
+const SCREEN_SIZE: Vec2 = egui::vec2(360.0, 740.0);
+/// Pointer moves a drag is split into
+const DRAG_STEPS: usize = 5;
+
+pub struct Harness {
+    ctx: egui::Context,
+    pub main_windows: MainWindows,
+    pub storage: MemoryStorage,
+    /// The tree of the last frame
+    tree: TreeUpdate,
+    screen_size: Vec2,
+}
+
+impl Harness {
+    /// Starts the app on a phone sized screen with `files` already in the storage
+    pub fn new(files: &[(&str, &str)]) -> Self {
+        let storage = MemoryStorage::default();
+
+        for (file, contents) in files {
+            storage
+                .files
+                .lock()
+                .unwrap()
+                .insert(file.to_string(), contents.to_string());
+        }
+
+        let mut main_windows = MainWindows::load(
+            Arc::new(storage.clone()),
+            Box::<MockNotifications>::default(),
+        );
+        main_windows.pin_today(NaiveDate::from_ymd_opt(2023, 6, 15).unwrap());
+
+        let ctx = egui::Context::default();
+        ctx.enable_accesskit();
+
+        let mut harness = Self {
+            ctx,
+            main_windows,
+            storage,
+            tree: TreeUpdate::default(),
+            screen_size: SCREEN_SIZE,
+        };
+
+        // Panels only settle in the second frame
+        harness.run(Vec::new());
+        harness.run(Vec::new());
+
+        harness
+    }
+
+    /// Changes the size of the screen, like turning the phone or resizing the window
+    pub fn resize(&mut self, screen_size: Vec2) {
+        self.screen_size = screen_size;
+
+        self.run(Vec::new());
+        self.run(Vec::new());
+    }
+
+    /// Runs one frame with `events`
+    pub fn run(&mut self, events: Vec<Event>) {
+        let raw_input = RawInput {
+            screen_rect: Some(Rect::from_min_size(Pos2::ZERO, self.screen_size)),
+            events,
+            ..Default::default()
+        };
+
+        let main_windows = &mut self.main_windows;
+        let full_output = self.ctx.run(raw_input, |ctx| main_windows.ui(ctx));
+
+        self.tree = full_output.platform_output.accesskit_update.unwrap();
+    }
+
+    pub fn tap(&mut self, name: &str) {
+        let position = self.center(name);
+
+        self.run(vec![
+            Event::PointerMoved(position),
+            pointer_button(position, true),
+        ]);
+        self.run(vec![pointer_button(position, false)]);
+        // Let the UI show the result of the tap
+        self.run(Vec::new());
+    }
+
+    pub fn drag(&mut self, name: &str, delta: Vec2) {
+        let start = self.center(name);
+
+        // Hovering first, otherwise the jump from the last position counts as dragging
+        self.run(vec![Event::PointerMoved(start)]);
+        self.run(vec![pointer_button(start, true)]);
+
+        for step in 1..=DRAG_STEPS {
+            let position = start + delta * step as f32 / DRAG_STEPS as f32;
+            self.run(vec![Event::PointerMoved(position)]);
+        }
+
+        self.run(vec![pointer_button(start + delta, false)]);
+        self.run(Vec::new());
+    }
+
+    pub fn press(&mut self, key: Key) {
+        self.press_with(Modifiers::NONE, key);
+    }
+
+    /// Presses `key` while holding `modifiers`, like Ctrl+Z
+    pub fn press_with(&mut self, modifiers: Modifiers, key: Key) {
+        self.run(vec![Event::Key {
+            key,
+            pressed: true,
+            repeat: false,
+            modifiers,
+        }]);
+        self.run(vec![Event::Key {
+            key,
+            pressed: false,
+            repeat: false,
+            modifiers,
+        }]);
+    }
+
+    /// Types `text` into the focused text field
+    pub fn type_text(&mut self, text: &str) {
+        self.run(vec![Event::Text(text.to_owned())]);
+    }
+
+    pub fn node(&self, name: &str) -> Option<&Node> {
+        self.tree
+            .nodes
+            .iter()
+            .map(|(_, node)| node)
+            .find(|node| node.name() == Some(name))
+    }
+
+    pub fn focused_name(&self) -> Option<&str> {
+        let focus = self.tree.focus?;
+
+        self.tree
+            .nodes
+            .iter()
+            .find(|(id, _)| *id == focus)
+            .and_then(|(_, node)| node.name())
+    }
+
+    /// The contents of a storage file, empty if it was never written
+    pub fn file(&self, file: &str) -> String {
+        self.storage
+            .files
+            .lock()
+            .unwrap()
+            .get(file)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn center(&self, name: &str) -> Pos2 {
+        let node = self
+            .node(name)
+            .unwrap_or_else(|| panic!("no widget named {:?}", name));
+        let bounds = node.bounds().unwrap();
+
+        Pos2::new(
+            ((bounds.x0 + bounds.x1) / 2.0) as f32,
+            ((bounds.y0 + bounds.y1) / 2.0) as f32,
+        )
+    }
+}
+
+fn pointer_button(pos: Pos2, pressed: bool) -> Event {
+    Event::PointerButton {
+        pos,
+        button: PointerButton::Primary,
+        pressed,
+        modifiers: Modifiers::NONE,
+    }
+}
@@ -0,0 +1,126 @@
+mod harness;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use android_rust_experiments::{
+    parse_date, AppState, DateFormat, Event, Language, Locale, Message, Settings,
+};
+use chrono::{NaiveDate, Weekday};
+use harness::Harness;
+
+const DATES: (&str, &str) = ("dates.dat", "2023 4 1\n2023 5 2\n");
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+/// The ids of the messages in a catalog
+fn message_ids(catalog: &str) -> BTreeSet<&str> {
+    catalog
+        .lines()
+        .filter(|line| !line.starts_with([' ', '#']))
+        .filter_map(|line| line.split_once(" ="))
+        .map(|(id, _)| id)
+        .collect()
+}
+
+#[test]
+fn every_catalog_has_the_english_messages() {
+    let english = message_ids(include_str!("../i18n/en.ftl"));
+    let german = message_ids(include_str!("../i18n/de.ftl"));
+
+    assert_eq!(
+        english.difference(&german).collect::<Vec<_>>(),
+        Vec::<&&str>::new()
+    );
+    assert_eq!(
+        german.difference(&english).collect::<Vec<_>>(),
+        Vec::<&&str>::new()
+    );
+}
+
+#[test]
+fn the_locale_of_the_system_picks_the_language() {
+    assert_eq!(Language::from_locale("de_DE.UTF-8"), Some(Language::German));
+    assert_eq!(Language::from_locale("de-AT"), Some(Language::German));
+    assert_eq!(Language::from_locale("en_US"), Some(Language::English));
+    assert_eq!(Language::from_locale("fr_FR.UTF-8"), None);
+    assert_eq!(Language::from_locale("C"), None);
+}
+
+#[test]
+fn dates_and_names_follow_the_language() {
+    let english = Locale::english();
+    let german = Locale::new(Language::German);
+
+    assert_eq!(english.format_date(date(2023, 6, 5)), "Jun 5, 2023");
+    assert_eq!(german.format_date(date(2023, 6, 5)), "5. Juni 2023");
+    assert_eq!(
+        german.format_long_date(date(2023, 7, 10)),
+        "Montag, 10. Juli 2023"
+    );
+    assert_eq!(german.month_name(3), "März");
+    assert_eq!(german.short_weekday_name(Weekday::Sun), "So");
+
+    assert_eq!(
+        german.text_with("events-on-day", &[("count", 1.into())]),
+        "1 Ereignis an diesem Tag"
+    );
+    assert_eq!(
+        german.text_with("events-on-day", &[("count", 2.into())]),
+        "2 Ereignisse an diesem Tag"
+    );
+
+    // The fixed formats stay the same in every language
+    assert_eq!(
+        DateFormat::YearMonthDay.format(date(2023, 6, 5), &german),
+        "2023-06-05"
+    );
+
+    // Typed dates in the numeric notation of the language
+    let today = date(2023, 6, 15);
+    assert_eq!(
+        parse_date("05.03.2024", today, DateFormat::Automatic, &german),
+        Some(date(2024, 3, 5))
+    );
+    assert_eq!(
+        parse_date("03/05/2024", today, DateFormat::Automatic, english),
+        Some(date(2024, 3, 5))
+    );
+}
+
+#[test]
+fn the_settings_override_the_language_of_the_system() {
+    let mut harness = Harness::new(&[DATES]);
+    assert!(harness.node("Dates").is_some());
+
+    harness.main_windows.set_system_language(Language::German);
+    harness.run(Vec::new());
+    assert!(harness.node("Termine").is_some());
+    assert!(harness.node("Heute eintragen").is_some());
+
+    let mut harness = Harness::new(&[DATES, ("settings.dat", "language en\n")]);
+
+    harness.main_windows.set_system_language(Language::German);
+    harness.run(Vec::new());
+    assert!(harness.node("Dates").is_some());
+}
+
+#[test]
+fn the_week_of_the_statistics_starts_on_the_week_start() {
+    // Thursday, after a Sunday and a Monday with events
+    let mut state = AppState::new(
+        vec![Event::new(date(2023, 6, 11)), Event::new(date(2023, 6, 12))],
+        Vec::new(),
+        BTreeMap::new(),
+        Settings::default(),
+        date(2023, 6, 15),
+    );
+    assert_eq!(state.statistics().week_events, 1);
+
+    state.apply(&Message::SettingsChanged(Settings {
+        week_start: Weekday::Sun,
+        ..Settings::default()
+    }));
+    assert_eq!(state.statistics().week_events, 2);
+}
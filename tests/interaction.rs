@@ -0,0 +1,214 @@
+mod harness;
+
+use std::{cell::RefCell, rc::Rc};
+
+use android_rust_experiments::{AppState, MessageBus, Screen, WriteOutcome};
+use egui::{vec2, Key};
+use harness::Harness;
+
+const DATES: (&str, &str) = ("dates.dat", "2023 4 1\n2023 5 2\n");
+
+#[test]
+fn tapping_plus_adds_an_entry() {
+    let mut harness = Harness::new(&[DATES]);
+
+    harness.tap("Dates");
+    harness.tap("Add event Jun 15, 2023");
+
+    assert!(harness.node("Delete event Jun 15, 2023").is_some());
+    assert_eq!(harness.file("dates.dat"), "2023 4 1\n2023 5 2\n2023 6 15\n");
+}
+
+#[test]
+fn tapping_x_deletes_an_entry_after_confirming() {
+    let mut harness = Harness::new(&[DATES]);
+
+    harness.tap("Dates");
+    harness.tap("Delete event Apr 1, 2023");
+
+    // Nothing is deleted before confirming
+    assert!(harness.node("Delete event Apr 1, 2023").is_some());
+    assert_eq!(harness.file("dates.dat"), DATES.1);
+
+    harness.tap("Delete");
+
+    assert!(harness.node("Delete event Apr 1, 2023").is_none());
+    assert_eq!(harness.file("dates.dat"), "2023 5 2\n");
+}
+
+#[test]
+fn tapping_x_deletes_an_entry_right_away_without_confirmation() {
+    let mut harness = Harness::new(&[DATES, ("settings.dat", "confirm_delete false\n")]);
+
+    harness.tap("Dates");
+    harness.tap("Delete event May 2, 2023");
+
+    assert!(harness.node("Delete event May 2, 2023").is_none());
+    assert_eq!(harness.file("dates.dat"), "2023 4 1\n");
+}
+
+#[test]
+fn escape_cancels_the_deletion() {
+    let mut harness = Harness::new(&[DATES]);
+
+    harness.tap("Dates");
+    harness.tap("Delete event Apr 1, 2023");
+    harness.press(Key::Escape);
+
+    assert!(harness.node("Delete").is_none());
+    assert!(harness.node("Delete event Apr 1, 2023").is_some());
+}
+
+#[test]
+fn tabs_switch_screens() {
+    let mut harness = Harness::new(&[DATES]);
+
+    assert!(harness.node("Events Last Year: 2").is_some());
+
+    harness.tap("Settings");
+    assert!(harness.node("Theme").is_some());
+    assert!(harness.node("Events Last Year: 2").is_none());
+
+    harness.tap("Dates");
+    assert!(harness.node("Year").is_some());
+
+    // Back returns through the visited screens to Home
+    assert!(harness.main_windows.back());
+    harness.run(Vec::new());
+    assert!(harness.node("Theme").is_some());
+
+    assert!(harness.main_windows.back());
+    assert!(!harness.main_windows.back());
+}
+
+#[test]
+fn keyboard_adds_an_entry() {
+    let mut harness = Harness::new(&[]);
+
+    harness.tap("Dates");
+
+    for _ in 0..10 {
+        if harness.focused_name() == Some("Add event Jun 15, 2023") {
+            break;
+        }
+
+        harness.press(Key::Tab);
+    }
+
+    harness.press(Key::Enter);
+    harness.run(Vec::new());
+
+    assert_eq!(harness.file("dates.dat"), "2023 6 15\n");
+}
+
+#[test]
+fn dragging_a_value_changes_and_saves_the_setting() {
+    let mut harness = Harness::new(&[DATES]);
+
+    harness.tap("Settings");
+    harness.drag("Months in statistics", vec2(-12.0, 0.0));
+
+    // egui only starts dragging after a few points, so the exact value is not checked
+    let months: u32 = harness
+        .file("settings.dat")
+        .lines()
+        .find_map(|line| line.strip_prefix("statistic_months "))
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((3..12).contains(&months), "{}", months);
+
+    harness.tap("Home");
+    let label = format!("Events Last {} Months: 2", months);
+    assert!(harness.node(&label).is_some());
+}
+
+#[test]
+fn quit_asks_the_event_loop_to_exit() {
+    let mut harness = Harness::new(&[]);
+
+    assert!(!harness.main_windows.is_quit_requested());
+
+    harness.tap("Quit");
+
+    assert!(harness.main_windows.is_quit_requested());
+}
+
+/// Records its lifecycle hooks
+struct EventsScreen {
+    calls: Rc<RefCell<Vec<&'static str>>>,
+}
+
+impl Screen for EventsScreen {
+    fn name(&self) -> &'static str {
+        "Events"
+    }
+
+    fn icon(&self) -> &'static str {
+        "📋"
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, state: &AppState, _bus: &mut MessageBus) {
+        ui.label(format!("{} events", state.events().len()));
+    }
+
+    fn on_enter(&mut self, _state: &AppState) {
+        self.calls.borrow_mut().push("enter");
+    }
+
+    fn on_leave(&mut self, _state: &AppState) {
+        self.calls.borrow_mut().push("leave");
+    }
+}
+
+#[test]
+fn registered_screens_get_a_tab() {
+    let mut harness = Harness::new(&[DATES]);
+    let calls = Rc::new(RefCell::new(Vec::new()));
+
+    harness.main_windows.register(Box::new(EventsScreen {
+        calls: calls.clone(),
+    }));
+    harness.run(Vec::new());
+
+    assert_eq!(
+        harness.main_windows.screen_names(),
+        ["Home", "Dates", "Settings", "Events"]
+    );
+
+    harness.tap("Events");
+    assert!(harness.node("2 events").is_some());
+
+    harness.tap("Home");
+    assert_eq!(*calls.borrow(), ["enter", "leave"]);
+}
+
+#[test]
+fn unknown_screens_are_not_opened() {
+    let mut harness = Harness::new(&[DATES]);
+
+    assert!(!harness.main_windows.navigate_to("Events"));
+    harness.run(Vec::new());
+
+    assert!(harness.node("Events Last Year: 2").is_some());
+}
+
+#[test]
+fn failed_writes_are_shown_until_dismissed() {
+    let mut harness = Harness::new(&[]);
+
+    harness.main_windows.on_write_outcome(WriteOutcome::Failed {
+        file: "dates.dat".to_owned(),
+        error: "disk full".to_owned(),
+    });
+    harness.run(Vec::new());
+    harness.run(Vec::new());
+
+    assert!(harness
+        .node("Could not save dates.dat: disk full")
+        .is_some());
+
+    harness.tap("Dismiss");
+
+    assert!(harness.node("Dismiss").is_none());
+}
@@ -0,0 +1,94 @@
+mod harness;
+
+use android_rust_experiments::FormFactor;
+use egui::{vec2, Event, Pos2, TouchDeviceId, TouchId, TouchPhase};
+use harness::Harness;
+
+const DATES: (&str, &str) = ("dates.dat", "2023 4 1\n2023 5 2\n");
+
+fn touch() -> Event {
+    Event::Touch {
+        device_id: TouchDeviceId(0),
+        id: TouchId(0),
+        phase: TouchPhase::Start,
+        pos: Pos2::new(10.0, 10.0),
+        force: 0.0,
+    }
+}
+
+#[test]
+fn the_form_factor_follows_size_aspect_and_input() {
+    assert_eq!(
+        FormFactor::detect(vec2(360.0, 740.0), false),
+        FormFactor::Mobile
+    );
+    assert_eq!(
+        FormFactor::detect(vec2(1024.0, 700.0), true),
+        FormFactor::Desktop
+    );
+
+    // An unfolded foldable in portrait, or a narrow desktop window
+    assert_eq!(
+        FormFactor::detect(vec2(700.0, 840.0), true),
+        FormFactor::Mobile
+    );
+    assert_eq!(
+        FormFactor::detect(vec2(700.0, 840.0), false),
+        FormFactor::Desktop
+    );
+    assert_eq!(
+        FormFactor::detect(vec2(840.0, 700.0), true),
+        FormFactor::Desktop
+    );
+}
+
+#[test]
+fn resizing_switches_the_layout() {
+    let mut harness = Harness::new(&[DATES]);
+
+    assert_eq!(harness.main_windows.form_factor(), FormFactor::Mobile);
+
+    harness.resize(vec2(1024.0, 700.0));
+    assert_eq!(harness.main_windows.form_factor(), FormFactor::Desktop);
+
+    harness.resize(vec2(700.0, 840.0));
+    assert_eq!(harness.main_windows.form_factor(), FormFactor::Desktop);
+
+    harness.run(vec![touch()]);
+    harness.run(Vec::new());
+    assert_eq!(harness.main_windows.form_factor(), FormFactor::Mobile);
+}
+
+#[test]
+fn the_settings_and_the_command_line_override_the_detection() {
+    let mut harness = Harness::new(&[("settings.dat", "layout mobile\n")]);
+    harness.resize(vec2(1024.0, 700.0));
+
+    assert_eq!(harness.main_windows.form_factor(), FormFactor::Mobile);
+
+    harness
+        .main_windows
+        .force_form_factor(Some(FormFactor::Desktop));
+    harness.run(Vec::new());
+
+    assert_eq!(harness.main_windows.form_factor(), FormFactor::Desktop);
+}
+
+#[test]
+fn desktop_shows_the_selected_event_next_to_the_list() {
+    let mut harness = Harness::new(&[DATES, ("settings.dat", "confirm_delete false\n")]);
+    harness.resize(vec2(1024.0, 700.0));
+
+    harness.tap("Dates");
+    assert!(harness.node("Select an event to see it here").is_some());
+
+    harness.tap("Apr 1, 2023");
+
+    assert!(harness.node("Saturday").is_some());
+    assert!(harness.node("75 days ago").is_some());
+
+    harness.tap("Delete");
+
+    assert_eq!(harness.file("dates.dat"), "2023 5 2\n");
+    assert!(harness.node("Select an event to see it here").is_some());
+}
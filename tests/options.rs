@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+
+use android_rust_experiments::{FileStorage, Options, Storage};
+use log::LevelFilter;
+
+fn parse(args: &[&str]) -> Result<Options, String> {
+    Options::parse(args.iter().map(|arg| arg.to_string()))
+}
+
+#[test]
+fn no_arguments_keep_the_defaults() {
+    assert_eq!(parse(&[]), Ok(Options::default()));
+}
+
+#[test]
+fn all_options_are_parsed() {
+    assert_eq!(
+        parse(&[
+            "--data-dir",
+            "/tmp/data",
+            "--mobile",
+            "--log-level=debug",
+            "--screen",
+            "Dates",
+        ]),
+        Ok(Options {
+            data_dir: Some(PathBuf::from("/tmp/data")),
+            mobile: true,
+            log_level: Some(LevelFilter::Debug),
+            screen: Some("Dates".to_owned()),
+            help: false,
+        })
+    );
+}
+
+#[test]
+fn invalid_arguments_are_errors() {
+    assert_eq!(
+        parse(&["--screen"]),
+        Err("--screen needs a value".to_owned())
+    );
+    assert_eq!(
+        parse(&["--log-level", "loud"]),
+        Err("Unknown log level loud".to_owned())
+    );
+    assert_eq!(parse(&["--fast"]), Err("Unknown option --fast".to_owned()));
+}
+
+#[test]
+fn files_are_kept_in_the_data_dir() {
+    let data_dir = std::env::temp_dir().join(format!("are-data-dir-{}", std::process::id()));
+    let storage = FileStorage::new(data_dir.join("nested"));
+
+    assert_eq!(storage.read("dates.dat"), None);
+
+    storage.write("dates.dat", "2023 4 1\n").unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(data_dir.join("nested/dates.dat")).unwrap(),
+        "2023 4 1\n"
+    );
+    assert_eq!(storage.read("dates.dat").as_deref(), Some("2023 4 1\n"));
+
+    std::fs::remove_dir_all(data_dir).unwrap();
+}
@@ -0,0 +1,144 @@
+use std::{
+    io,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use android_rust_experiments::{MemoryStorage, Persistence, Storage, WriteOutcome};
+
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Records the writes that reach it, fails them for files named "broken.dat"
+#[derive(Default)]
+struct RecordingStorage {
+    memory: MemoryStorage,
+    writes: Mutex<Vec<String>>,
+}
+
+impl Storage for RecordingStorage {
+    fn read(&self, file: &str) -> Option<String> {
+        self.memory.read(file)
+    }
+
+    fn write(&self, file: &str, contents: &str) -> io::Result<()> {
+        if file == "broken.dat" {
+            return Err(io::Error::other("disk full"));
+        }
+
+        self.writes.lock().unwrap().push(file.to_owned());
+        self.memory.write(file, contents)
+    }
+}
+
+fn persistence() -> (
+    Arc<RecordingStorage>,
+    Persistence,
+    mpsc::Receiver<WriteOutcome>,
+) {
+    let storage = Arc::new(RecordingStorage::default());
+    let (report, outcomes) = mpsc::channel();
+
+    let persistence = Persistence::new(storage.clone(), DEBOUNCE, move |outcome| {
+        report.send(outcome).unwrap();
+    });
+
+    (storage, persistence, outcomes)
+}
+
+#[test]
+fn writes_in_quick_succession_are_coalesced() {
+    let (storage, persistence, outcomes) = persistence();
+
+    for contents in ["1", "2", "3"] {
+        persistence.write("dates.dat", contents).unwrap();
+    }
+
+    // Nothing is written before the debounce time has passed
+    assert!(storage.writes.lock().unwrap().is_empty());
+
+    let outcome = outcomes.recv_timeout(Duration::from_secs(5)).unwrap();
+
+    assert_eq!(
+        outcome,
+        WriteOutcome::Written {
+            file: "dates.dat".to_owned()
+        }
+    );
+    assert_eq!(*storage.writes.lock().unwrap(), ["dates.dat"]);
+    assert_eq!(storage.read("dates.dat").as_deref(), Some("3"));
+}
+
+#[test]
+fn flush_writes_right_away() {
+    let (storage, persistence, _outcomes) = persistence();
+
+    persistence.write("dates.dat", "1").unwrap();
+    persistence.write("goals.dat", "2").unwrap();
+    persistence.flush();
+
+    assert_eq!(*storage.writes.lock().unwrap(), ["dates.dat", "goals.dat"]);
+}
+
+#[test]
+fn reads_see_pending_writes() {
+    let (_storage, persistence, _outcomes) = persistence();
+
+    persistence.write("settings.dat", "theme dark\n").unwrap();
+
+    assert_eq!(
+        persistence.read("settings.dat").as_deref(),
+        Some("theme dark\n")
+    );
+}
+
+#[test]
+fn failures_are_reported() {
+    let (_storage, persistence, outcomes) = persistence();
+
+    persistence.write("broken.dat", "1").unwrap();
+
+    assert_eq!(
+        outcomes.recv_timeout(Duration::from_secs(5)).unwrap(),
+        WriteOutcome::Failed {
+            file: "broken.dat".to_owned(),
+            error: "disk full".to_owned()
+        }
+    );
+}
+
+#[test]
+fn dropping_writes_the_rest() {
+    let (storage, persistence, _outcomes) = persistence();
+
+    persistence.write("dates.dat", "1").unwrap();
+    drop(persistence);
+
+    assert_eq!(*storage.writes.lock().unwrap(), ["dates.dat"]);
+}
+
+#[test]
+fn writes_do_not_wait_for_the_storage() {
+    struct SlowStorage;
+
+    impl Storage for SlowStorage {
+        fn read(&self, _file: &str) -> Option<String> {
+            None
+        }
+
+        fn write(&self, _file: &str, _contents: &str) -> io::Result<()> {
+            thread::sleep(Duration::from_millis(200));
+            Ok(())
+        }
+    }
+
+    let persistence = Persistence::new(Arc::new(SlowStorage), Duration::ZERO, |_| {});
+
+    let start = std::time::Instant::now();
+
+    for _ in 0..10 {
+        persistence.write("dates.dat", "1").unwrap();
+    }
+
+    assert!(start.elapsed() < Duration::from_millis(200));
+}
@@ -0,0 +1,146 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use android_rust_experiments::{FrameTimes, MemoryStorage, Profiler, Storage, TRACE_FILE};
+use egui::{epaint::ImageDelta, Color32, ColorImage, TextureId, TextureOptions, TexturesDelta};
+
+fn times() -> FrameTimes {
+    FrameTimes {
+        ui: Duration::from_millis(3),
+        tessellate: Duration::from_millis(1),
+        paint: Duration::from_millis(2),
+    }
+}
+
+fn texture(size: usize) -> ImageDelta {
+    ImageDelta::full(
+        ColorImage::new([size, size], Color32::WHITE),
+        TextureOptions::default(),
+    )
+}
+
+/// Runs a frame of `ctx` and tessellates it
+fn frame(
+    ctx: &egui::Context,
+    profiler: &mut Profiler,
+    events: Vec<egui::Event>,
+) -> Vec<egui::ClippedPrimitive> {
+    let raw_input = egui::RawInput {
+        screen_rect: Some(egui::Rect::from_min_size(
+            egui::Pos2::ZERO,
+            egui::vec2(800.0, 600.0),
+        )),
+        events,
+        ..Default::default()
+    };
+
+    let output = ctx.run(raw_input, |ctx| profiler.ui(ctx));
+    ctx.tessellate(output.shapes)
+}
+
+fn f12() -> egui::Event {
+    egui::Event::Key {
+        key: egui::Key::F12,
+        pressed: true,
+        repeat: false,
+        modifiers: egui::Modifiers::NONE,
+    }
+}
+
+#[test]
+fn texture_memory_follows_the_deltas() {
+    let mut profiler = Profiler::new(Arc::new(MemoryStorage::default()));
+    let font = TextureId::Managed(0);
+    let image = TextureId::Managed(1);
+
+    let mut delta = TexturesDelta::default();
+    delta.set.push((font, texture(16)));
+    delta.set.push((image, texture(8)));
+    profiler.frame_finished(Instant::now(), times(), &[], &delta);
+
+    assert_eq!(profiler.last_frame().unwrap().texture_bytes, (256 + 64) * 4);
+
+    // A partial update does not change the size
+    let mut delta = TexturesDelta::default();
+    delta.set.push((
+        font,
+        ImageDelta::partial(
+            [0, 0],
+            ColorImage::new([2, 2], Color32::RED),
+            TextureOptions::default(),
+        ),
+    ));
+    delta.free.push(image);
+    profiler.frame_finished(Instant::now(), times(), &[], &delta);
+
+    assert_eq!(profiler.last_frame().unwrap().texture_bytes, 256 * 4);
+}
+
+#[test]
+fn f12_toggles_the_overlay_with_mesh_counts() {
+    let ctx = egui::Context::default();
+    let mut profiler = Profiler::new(Arc::new(MemoryStorage::default()));
+
+    let hidden = frame(&ctx, &mut profiler, Vec::new());
+    assert!(hidden.is_empty());
+
+    frame(&ctx, &mut profiler, vec![f12()]);
+    assert!(profiler.is_overlay_visible());
+
+    // New areas are only measured in their first frame
+    let shown = frame(&ctx, &mut profiler, Vec::new());
+    assert!(!shown.is_empty());
+
+    profiler.frame_finished(Instant::now(), times(), &shown, &TexturesDelta::default());
+
+    let last_frame = profiler.last_frame().unwrap();
+    assert_eq!(last_frame.meshes, shown.len());
+    assert!(last_frame.vertices > 0);
+
+    frame(&ctx, &mut profiler, vec![f12()]);
+    assert!(!profiler.is_overlay_visible());
+}
+
+#[test]
+fn fps_and_averages_cover_the_recent_frames() {
+    let mut profiler = Profiler::new(Arc::new(MemoryStorage::default()));
+    let start = Instant::now();
+
+    for i in 0..11 {
+        let started = start + Duration::from_millis(100 * i);
+        profiler.frame_finished(started, times(), &[], &TexturesDelta::default());
+    }
+
+    assert!((profiler.fps() - 10.0).abs() < 0.01);
+    assert_eq!(profiler.average_times(), times());
+}
+
+#[test]
+fn recorded_traces_are_written_as_chrome_traces() {
+    let storage = MemoryStorage::default();
+    let mut profiler = Profiler::new(Arc::new(storage.clone()));
+
+    profiler.frame_finished(Instant::now(), times(), &[], &TexturesDelta::default());
+    profiler.start_recording();
+    assert!(profiler.is_recording());
+
+    let start = Instant::now();
+    profiler.frame_finished(start, times(), &[], &TexturesDelta::default());
+    profiler.frame_finished(
+        start + Duration::from_millis(16),
+        times(),
+        &[],
+        &TexturesDelta::default(),
+    );
+    profiler.stop_recording().unwrap();
+
+    assert!(!profiler.is_recording());
+
+    let trace = storage.read(TRACE_FILE).unwrap();
+    assert!(trace.starts_with("{\"traceEvents\":["));
+    assert_eq!(trace.matches("\"name\":\"frame\"").count(), 2);
+    assert!(trace.contains("{\"name\":\"frame\",\"ph\":\"X\",\"ts\":16000,\"dur\":6000,"));
+    assert!(trace.contains("{\"name\":\"tessellate\",\"ph\":\"X\",\"ts\":19000,\"dur\":1000,"));
+}
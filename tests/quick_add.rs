@@ -0,0 +1,110 @@
+mod harness;
+
+use android_rust_experiments::{AppState, Event};
+use chrono::NaiveDate;
+use harness::Harness;
+
+const DATES: (&str, &str) = ("dates.dat", "2023 4 1\n2023 5 2\n");
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+/// About the seconds egui assumes for `frames` frames without a clock
+fn wait_frames(harness: &mut Harness, frames: usize) {
+    for _ in 0..frames {
+        harness.run(Vec::new());
+    }
+}
+
+#[test]
+fn log_today_ignores_a_double_tap() {
+    let mut harness = Harness::new(&[DATES]);
+
+    harness.tap("Log today");
+    harness.tap("Log today");
+
+    assert_eq!(harness.file("dates.dat"), "2023 4 1\n2023 5 2\n2023 6 15\n");
+
+    // A second later it is a new tap
+    wait_frames(&mut harness, 60);
+    harness.tap("Log today");
+
+    assert_eq!(
+        harness.file("dates.dat"),
+        "2023 4 1\n2023 5 2\n2023 6 15\n2023 6 15\n"
+    );
+}
+
+#[test]
+fn the_toast_undoes_log_today() {
+    let mut harness = Harness::new(&[DATES]);
+
+    harness.tap("Log today");
+    assert!(harness.node("Logged Jun 15, 2023").is_some());
+
+    harness.tap("Undo");
+
+    assert_eq!(harness.file("dates.dat"), DATES.1);
+    assert!(harness.node("Logged Jun 15, 2023").is_none());
+}
+
+#[test]
+fn the_toast_goes_away() {
+    let mut harness = Harness::new(&[DATES]);
+
+    harness.tap("Log today");
+    wait_frames(&mut harness, 6 * 60);
+
+    assert!(harness.node("Undo").is_none());
+    assert_eq!(harness.file("dates.dat"), "2023 4 1\n2023 5 2\n2023 6 15\n");
+}
+
+#[test]
+fn the_shortcut_logs_today_to_the_default_tracker() {
+    let mut harness = Harness::new(&[DATES, ("settings.dat", "default_tracker work\n")]);
+
+    let state = harness.main_windows.state();
+    assert_eq!(state.settings().default_tracker, "work");
+    assert!(state.reminder_rules().contains_key("work"));
+
+    harness.main_windows.log_today();
+
+    assert_eq!(
+        harness.file("dates.dat"),
+        "2023 4 1\n2023 5 2\n2023 6 15 work\n"
+    );
+    assert!(harness.main_windows.undo());
+    assert_eq!(harness.file("dates.dat"), DATES.1);
+}
+
+#[test]
+fn undoing_log_today_keeps_the_other_trackers_events() {
+    let dates = ("dates.dat", "2023 4 1\n2023 6 15\n");
+    let mut harness = Harness::new(&[dates, ("settings.dat", "default_tracker work\n")]);
+
+    harness.tap("Log today");
+
+    let events: Vec<_> = harness
+        .main_windows
+        .state()
+        .events()
+        .iter()
+        .cloned()
+        .collect();
+    assert_eq!(
+        events,
+        [
+            Event::new(date(2023, 4, 1)),
+            Event::new(date(2023, 6, 15)),
+            Event::of_tracker(date(2023, 6, 15), "work"),
+        ]
+    );
+
+    // The trackers survive a restart
+    let state = AppState::load(&harness.storage, date(2023, 6, 15));
+    assert_eq!(state.events().iter().cloned().collect::<Vec<_>>(), events);
+
+    harness.tap("Undo");
+    assert_eq!(harness.file("dates.dat"), dates.1);
+}
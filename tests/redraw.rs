@@ -0,0 +1,75 @@
+use std::time::{Duration, Instant};
+
+use android_rust_experiments::RedrawScheduler;
+use winit::event_loop::ControlFlow;
+
+#[test]
+fn nothing_is_drawn_until_requested() {
+    let mut scheduler = RedrawScheduler::default();
+
+    assert!(!scheduler.is_due(Instant::now()));
+    assert_eq!(scheduler.control_flow(), ControlFlow::Wait);
+
+    scheduler.request_redraw();
+
+    assert!(scheduler.is_due(Instant::now()));
+}
+
+#[test]
+fn idle_frames_sleep_until_the_next_event() {
+    let mut scheduler = RedrawScheduler::default();
+
+    scheduler.request_redraw();
+    scheduler.frame_finished(Instant::now(), Duration::MAX);
+
+    assert!(!scheduler.is_due(Instant::now()));
+    assert_eq!(scheduler.control_flow(), ControlFlow::Wait);
+}
+
+#[test]
+fn animations_wake_up_after_repaint_after() {
+    let mut scheduler = RedrawScheduler::default();
+
+    scheduler.frame_finished(Instant::now(), Duration::from_millis(50));
+
+    assert!(!scheduler.is_due(Instant::now()));
+    assert!(scheduler.is_due(Instant::now() + Duration::from_millis(60)));
+    assert!(matches!(
+        scheduler.control_flow(),
+        ControlFlow::WaitUntil(_)
+    ));
+}
+
+#[test]
+fn frames_and_wakeups_are_counted() {
+    let mut scheduler = RedrawScheduler::default();
+
+    for _ in 0..3 {
+        scheduler.on_wakeup();
+    }
+
+    let started = Instant::now() - Duration::from_millis(4);
+    scheduler.frame_finished(started, Duration::MAX);
+
+    let stats = scheduler.stats();
+    assert_eq!(stats.frames, 1);
+    assert_eq!(stats.wakeups, 3);
+    assert!(stats.average_frame_time() >= Duration::from_millis(4));
+}
+
+#[test]
+fn delayed_repaints_wait_for_the_delay() {
+    let mut scheduler = RedrawScheduler::default();
+
+    scheduler.request_redraw_after(Duration::from_millis(500));
+
+    assert!(!scheduler.is_due(Instant::now()));
+    assert!(scheduler.is_due(Instant::now() + Duration::from_millis(600)));
+
+    // The earlier of two requests wins
+    scheduler.request_redraw_after(Duration::from_secs(60));
+    assert!(scheduler.is_due(Instant::now() + Duration::from_millis(600)));
+
+    scheduler.request_redraw_after(Duration::ZERO);
+    assert!(scheduler.is_due(Instant::now()));
+}
@@ -0,0 +1,250 @@
+use std::collections::BTreeMap;
+
+use android_rust_experiments::{
+    AppState, Event, MemoryStorage, MockNotifications, ReminderKind, ReminderRules, Reminders,
+    Settings,
+};
+use chrono::{Datelike, Days, Local, Months, NaiveDate, TimeZone};
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn state_on(today: NaiveDate, dates: Vec<NaiveDate>, rules: ReminderRules) -> AppState {
+    let mut all_rules = BTreeMap::new();
+    all_rules.insert("default".to_owned(), rules);
+
+    AppState::new(
+        dates.into_iter().map(Event::new).collect(),
+        Vec::new(),
+        all_rules,
+        Settings::default(),
+        today,
+    )
+}
+
+fn state(dates: Vec<NaiveDate>, rules: ReminderRules) -> AppState {
+    state_on(Local::now().date_naive(), dates, rules)
+}
+
+const RECURRING: ReminderRules = ReminderRules {
+    streak_at_risk_days: None,
+    recurring_days: Some(14),
+};
+
+#[test]
+fn streak_at_risk_is_due_before_the_month_ends() {
+    let today = Local::now().date_naive();
+    let previous_month = today.with_day(1).unwrap() - Months::new(1);
+    let state = state(
+        vec![previous_month],
+        ReminderRules {
+            streak_at_risk_days: Some(3),
+            recurring_days: None,
+        },
+    );
+
+    let reminders = Reminders::new(Box::<MockNotifications>::default());
+
+    let scheduled = reminders.schedule("default", &state);
+
+    assert_eq!(scheduled.len(), 1);
+    assert_eq!(scheduled[0].kind, ReminderKind::StreakAtRisk);
+    assert_eq!(
+        scheduled[0].due,
+        today.with_day(1).unwrap() + Months::new(1) - Days::new(3)
+    );
+}
+
+#[test]
+fn no_streak_reminder_once_the_month_has_an_event() {
+    let today = Local::now().date_naive();
+    let state = state(
+        vec![today],
+        ReminderRules {
+            streak_at_risk_days: Some(3),
+            recurring_days: None,
+        },
+    );
+
+    let reminders = Reminders::new(Box::<MockNotifications>::default());
+
+    assert!(reminders.schedule("default", &state).is_empty());
+}
+
+#[test]
+fn due_reminders_are_shown_once() {
+    let today = Local::now().date_naive();
+    let state = state(vec![today - Days::new(20)], RECURRING);
+    let storage = MemoryStorage::default();
+
+    let backend = MockNotifications::default();
+    let mut reminders = Reminders::new(Box::new(backend.clone()));
+
+    assert!(reminders.notify_due(&state, &storage));
+    assert!(!reminders.notify_due(&state, &storage));
+
+    assert_eq!(backend.shown.borrow().len(), 1);
+}
+
+#[test]
+fn reminders_are_not_shown_before_they_are_due() {
+    let today = Local::now().date_naive();
+    let state = state(vec![today - Days::new(2)], RECURRING);
+
+    let backend = MockNotifications::default();
+    let mut reminders = Reminders::new(Box::new(backend.clone()));
+
+    reminders.notify_due(&state, &MemoryStorage::default());
+
+    assert!(backend.shown.borrow().is_empty());
+}
+
+#[test]
+fn later_reminders_are_scheduled_with_the_platform() {
+    let state = state_on(date(2023, 6, 15), vec![date(2023, 6, 13)], RECURRING);
+    let storage = MemoryStorage::default();
+
+    let backend = MockNotifications::default();
+    let mut reminders = Reminders::new(Box::new(backend.clone()));
+
+    reminders.notify_due(&state, &storage);
+    reminders.notify_due(&state, &storage);
+
+    let scheduled = backend.scheduled.borrow();
+    assert_eq!(scheduled.len(), 1);
+    assert_eq!(
+        scheduled[0].1,
+        Local
+            .from_local_datetime(&date(2023, 6, 27).and_hms_opt(9, 0, 0).unwrap())
+            .unwrap()
+    );
+    assert!(backend.shown.borrow().is_empty());
+}
+
+#[test]
+fn scheduled_reminders_are_cancelled_when_they_no_longer_apply() {
+    let storage = MemoryStorage::default();
+    let backend = MockNotifications::default();
+    let mut reminders = Reminders::new(Box::new(backend.clone()));
+
+    let dates = vec![date(2023, 6, 13)];
+    let state = state_on(date(2023, 6, 15), dates.clone(), RECURRING);
+    reminders.notify_due(&state, &storage);
+    assert_eq!(backend.scheduled.borrow().len(), 1);
+
+    let state = state_on(date(2023, 6, 15), dates, ReminderRules::default());
+    reminders.notify_due(&state, &storage);
+    assert!(backend.scheduled.borrow().is_empty());
+}
+
+#[test]
+fn every_tracker_with_rules_is_reminded_with_its_own_notification() {
+    let mut rules = BTreeMap::new();
+    rules.insert("default".to_owned(), RECURRING);
+    rules.insert("running".to_owned(), RECURRING);
+    let state = AppState::new(
+        vec![Event::new(date(2023, 5, 1))],
+        Vec::new(),
+        rules,
+        Settings::default(),
+        date(2023, 6, 15),
+    );
+
+    let backend = MockNotifications::default();
+    let mut reminders = Reminders::new(Box::new(backend.clone()));
+
+    reminders.notify_due(&state, &MemoryStorage::default());
+
+    let shown = backend.shown.borrow();
+    assert_eq!(shown.len(), 2);
+    assert_ne!(shown[0].id, shown[1].id);
+}
+
+#[test]
+fn shown_reminders_are_not_shown_again_after_a_restart() {
+    let state = state_on(date(2023, 6, 15), vec![date(2023, 5, 1)], RECURRING);
+    let storage = MemoryStorage::default();
+
+    let backend = MockNotifications::default();
+    Reminders::new(Box::new(backend.clone())).notify_due(&state, &storage);
+    assert_eq!(backend.shown.borrow().len(), 1);
+
+    let backend = MockNotifications::default();
+    Reminders::load(&storage, Box::new(backend.clone())).notify_due(&state, &storage);
+    assert!(backend.shown.borrow().is_empty());
+}
+
+#[test]
+fn timer_reminders_count_as_shown_once_they_fired() {
+    let dates = vec![date(2023, 6, 13)];
+    let state = state_on(date(2023, 6, 15), dates.clone(), RECURRING);
+    let storage = MemoryStorage::default();
+
+    let backend = MockNotifications::default();
+    let mut reminders = Reminders::new(Box::new(backend.clone()));
+
+    assert!(!reminders.notify_due(&state, &storage));
+    assert!(reminders.shown().is_empty());
+
+    backend.fire_scheduled();
+    assert!(reminders.notify_due(&state, &storage));
+    assert_eq!(reminders.shown().len(), 1);
+
+    let state = state_on(date(2023, 6, 28), dates, RECURRING);
+    assert!(!reminders.notify_due(&state, &storage));
+    assert_eq!(backend.shown.borrow().len(), 1);
+}
+
+#[test]
+fn timer_reminders_are_scheduled_again_after_a_restart_before_they_fired() {
+    let state = state_on(date(2023, 6, 15), vec![date(2023, 6, 13)], RECURRING);
+    let storage = MemoryStorage::default();
+
+    let backend = MockNotifications::default();
+    Reminders::new(Box::new(backend.clone())).notify_due(&state, &storage);
+
+    let backend = MockNotifications::default();
+    Reminders::load(&storage, Box::new(backend.clone())).notify_due(&state, &storage);
+    assert_eq!(backend.scheduled.borrow().len(), 1);
+}
+
+#[test]
+fn persistent_reminders_count_as_shown_once_they_are_scheduled() {
+    let state = state_on(date(2023, 6, 15), vec![date(2023, 6, 13)], RECURRING);
+
+    let mut backend = MockNotifications::default();
+    backend.is_persistent = true;
+    let mut reminders = Reminders::new(Box::new(backend.clone()));
+
+    assert!(reminders.notify_due(&state, &MemoryStorage::default()));
+    assert_eq!(reminders.shown().len(), 1);
+    assert_eq!(backend.scheduled.borrow().len(), 1);
+}
+
+#[test]
+fn reminders_only_count_the_events_of_their_tracker() {
+    let mut rules = BTreeMap::new();
+    rules.insert("default".to_owned(), RECURRING);
+    rules.insert("running".to_owned(), RECURRING);
+    let state = AppState::new(
+        vec![
+            Event::new(date(2023, 6, 13)),
+            Event::of_tracker(date(2023, 5, 1), "running"),
+        ],
+        Vec::new(),
+        rules,
+        Settings::default(),
+        date(2023, 6, 15),
+    );
+
+    let backend = MockNotifications::default();
+    let mut reminders = Reminders::new(Box::new(backend.clone()));
+
+    reminders.notify_due(&state, &MemoryStorage::default());
+
+    let shown = backend.shown.borrow();
+    assert_eq!(shown.len(), 1);
+    assert_eq!(shown[0].title, "Reminder: running");
+    assert_eq!(backend.scheduled.borrow().len(), 1);
+}
@@ -0,0 +1,84 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use android_rust_experiments::{
+    AppState, Backend, DateFormat, FormFactor, GraphicsSettings, Language, MainWindows,
+    MemoryStorage, MockNotifications, PowerPreference, PresentMode, Settings, Storage, Theme,
+};
+use chrono::{NaiveDate, Weekday};
+use egui::Color32;
+
+fn today() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2023, 6, 15).unwrap()
+}
+
+#[test]
+fn every_setting_survives_a_restart() {
+    let settings = Settings {
+        theme: Theme::Dark,
+        accent_color: Color32::from_rgb(200, 16, 46),
+        text_scale: 1.25,
+        language: Some(Language::German),
+        week_start: Weekday::Thu,
+        date_format: DateFormat::DayMonthYear,
+        picker_years: 30,
+        statistic_months: 6,
+        confirm_delete: false,
+        form_factor: Some(FormFactor::Desktop),
+        graphics: GraphicsSettings {
+            msaa_samples: 4,
+            present_mode: PresentMode::Mailbox,
+            power_preference: PowerPreference::HighPerformance,
+            backend: Backend::Gl,
+        },
+        default_tracker: "work".to_owned(),
+    };
+    assert_ne!(settings, Settings::default());
+
+    let storage = MemoryStorage::default();
+    let state = AppState::new(
+        Vec::new(),
+        Vec::new(),
+        BTreeMap::new(),
+        settings.clone(),
+        today(),
+    );
+    MainWindows::new(
+        Arc::new(storage.clone()),
+        state,
+        Box::<MockNotifications>::default(),
+    )
+    .flush();
+
+    assert_eq!(*AppState::load(&storage, today()).settings(), settings);
+}
+
+#[test]
+fn unreadable_settings_keep_their_default() {
+    let storage = MemoryStorage::default();
+    storage
+        .write(
+            "settings.dat",
+            "theme purple\n\
+         accent_color zzz\n\
+         text_scale\n\
+         week_start 9\n\
+         picker_years many\n\
+         confirm_delete maybe\n\
+         unknown 1\n\
+         \n\
+         statistic_months 6\n\
+         week_start 6\n",
+        )
+        .unwrap();
+
+    let settings = AppState::load(&storage, today()).settings().clone();
+
+    assert_eq!(
+        settings,
+        Settings {
+            statistic_months: 6,
+            week_start: Weekday::Sun,
+            ..Settings::default()
+        }
+    );
+}
@@ -0,0 +1,121 @@
+mod harness;
+
+use android_rust_experiments::{parse_date, DateFormat, Locale};
+use chrono::NaiveDate;
+use egui::{vec2, Key, Modifiers};
+use harness::Harness;
+
+const DATES: (&str, &str) = ("dates.dat", "2023 4 1\n2023 5 2\n");
+const NO_CONFIRMATION: (&str, &str) = ("settings.dat", "confirm_delete false\n");
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn desktop(files: &[(&str, &str)]) -> Harness {
+    let mut harness = Harness::new(files);
+    harness.main_windows.navigate_to("Dates");
+    harness.resize(vec2(1024.0, 700.0));
+    harness
+}
+
+#[test]
+fn typed_dates_are_parsed() {
+    // A Thursday
+    let today = date(2023, 6, 15);
+    let parse = |text| parse_date(text, today, DateFormat::DayMonthYear, Locale::english());
+
+    assert_eq!(parse("Today"), Some(today));
+    assert_eq!(parse(" yesterday "), Some(date(2023, 6, 14)));
+    assert_eq!(parse("2024-03-05"), Some(date(2024, 3, 5)));
+    assert_eq!(parse("05.03.2024"), Some(date(2024, 3, 5)));
+    assert_eq!(parse("3 days ago"), Some(date(2023, 6, 12)));
+    assert_eq!(parse("a week ago"), Some(date(2023, 6, 8)));
+
+    assert_eq!(parse("friday"), Some(date(2023, 6, 9)));
+    assert_eq!(parse("thu"), Some(today));
+    assert_eq!(parse("last thursday"), Some(date(2023, 6, 8)));
+
+    assert_eq!(parse("someday"), None);
+    assert_eq!(parse("2023-02-30"), None);
+}
+
+#[test]
+fn ctrl_n_focuses_the_date_field_and_enter_adds_the_date() {
+    let mut harness = desktop(&[DATES]);
+
+    harness.press_with(Modifiers::COMMAND, Key::N);
+    assert_eq!(harness.focused_name(), Some("New event date"));
+
+    harness.type_text("last friday");
+    harness.run(Vec::new());
+    assert!(harness.node("Jun 9, 2023").is_some());
+
+    harness.press(Key::Enter);
+    harness.run(Vec::new());
+
+    assert_eq!(harness.file("dates.dat"), "2023 4 1\n2023 5 2\n2023 6 9\n");
+    assert_eq!(harness.focused_name(), Some("New event date"));
+}
+
+#[test]
+fn ctrl_z_undoes_and_ctrl_y_redoes() {
+    let mut harness = desktop(&[DATES, NO_CONFIRMATION]);
+
+    harness.tap("Delete event Apr 1, 2023");
+    assert_eq!(harness.file("dates.dat"), "2023 5 2\n");
+
+    harness.press_with(Modifiers::COMMAND, Key::Z);
+    assert_eq!(harness.file("dates.dat"), "2023 4 1\n2023 5 2\n");
+
+    harness.press_with(Modifiers::COMMAND, Key::Y);
+    assert_eq!(harness.file("dates.dat"), "2023 5 2\n");
+
+    harness.press_with(Modifiers::COMMAND, Key::Z);
+    assert!(!harness.main_windows.undo());
+
+    assert!(harness.main_windows.redo());
+    assert_eq!(harness.file("dates.dat"), "2023 5 2\n");
+}
+
+#[test]
+fn arrows_select_events_and_delete_removes_the_selected_one() {
+    let mut harness = desktop(&[DATES, NO_CONFIRMATION]);
+
+    harness.press(Key::ArrowDown);
+    harness.press(Key::ArrowDown);
+    harness.run(Vec::new());
+    assert!(harness.node("Tuesday").is_some());
+
+    harness.press(Key::ArrowUp);
+    harness.run(Vec::new());
+    assert!(harness.node("Saturday").is_some());
+
+    harness.press(Key::Delete);
+    assert_eq!(harness.file("dates.dat"), "2023 5 2\n");
+
+    harness.press(Key::F1);
+    harness.run(Vec::new());
+    assert!(harness.node("Show or hide the shortcuts").is_some());
+}
+
+#[test]
+fn arrows_step_through_the_events_of_one_date() {
+    let mut harness = desktop(&[
+        ("dates.dat", "2023 4 1\n2023 4 1\n2023 4 1\n2023 5 2\n"),
+        NO_CONFIRMATION,
+    ]);
+
+    for _ in 0..4 {
+        harness.press(Key::ArrowDown);
+    }
+    harness.run(Vec::new());
+    assert!(harness.node("Tuesday").is_some());
+
+    harness.press(Key::ArrowUp);
+    harness.run(Vec::new());
+    assert!(harness.node("Saturday").is_some());
+
+    harness.press(Key::Delete);
+    assert_eq!(harness.file("dates.dat"), "2023 4 1\n2023 4 1\n2023 5 2\n");
+}
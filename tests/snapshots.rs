@@ -0,0 +1,176 @@
+//! Compares screenshots from the software renderer with the images in `tests/snapshots`.
+//!
+//! Run with `UPDATE_SNAPSHOTS=1` to replace the images after an intended change of the UI.
+
+use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
+
+use android_rust_experiments::{
+    load_png, save_png, screenshot, AppState, Event, Goal, GoalPeriod, Language, MainWindows,
+    MemoryStorage, MockNotifications, Settings,
+};
+use chrono::NaiveDate;
+use egui::{vec2, ColorImage, Vec2};
+
+struct ScreenSize {
+    name: &'static str,
+    logical_size: Vec2,
+    pixels_per_point: f32,
+}
+
+const PHONE: ScreenSize = ScreenSize {
+    name: "phone",
+    logical_size: vec2(360.0, 740.0),
+    pixels_per_point: 2.0,
+};
+
+const DESKTOP: ScreenSize = ScreenSize {
+    name: "desktop",
+    logical_size: vec2(1024.0, 700.0),
+    pixels_per_point: 1.0,
+};
+
+/// Channels may differ this much, e.g. from float rounding on another CPU
+const CHANNEL_TOLERANCE: u8 = 8;
+/// Fraction of pixels that may differ by more than `CHANNEL_TOLERANCE`
+const PIXEL_TOLERANCE: f32 = 0.001;
+
+fn main_windows(screen: &str, language: Language) -> MainWindows {
+    let today = NaiveDate::from_ymd_opt(2023, 6, 15).unwrap();
+    let dates = [(2023, 4, 1), (2023, 5, 2), (2023, 5, 20), (2023, 6, 3)]
+        .iter()
+        .map(|&(year, month, day)| NaiveDate::from_ymd_opt(year, month, day).unwrap())
+        .map(Event::new)
+        .collect();
+    let goals = vec![Goal {
+        target: 2,
+        period: GoalPeriod::Month,
+    }];
+    let state = AppState::new(dates, goals, BTreeMap::new(), Settings::default(), today);
+
+    let mut main_windows = MainWindows::new(
+        Arc::new(MemoryStorage::default()),
+        state,
+        Box::<MockNotifications>::default(),
+    );
+    main_windows.pin_today(today);
+    main_windows.set_system_language(language);
+    assert!(main_windows.navigate_to(screen));
+
+    main_windows
+}
+
+fn render(screen: &str, language: Language, size: &ScreenSize) -> ColorImage {
+    let mut main_windows = main_windows(screen, language);
+
+    screenshot(
+        &egui::Context::default(),
+        size.logical_size,
+        size.pixels_per_point,
+        |ctx| main_windows.ui(ctx),
+    )
+}
+
+fn differing_pixels(expected: &ColorImage, actual: &ColorImage) -> usize {
+    expected
+        .pixels
+        .iter()
+        .zip(&actual.pixels)
+        .filter(|(expected, actual)| {
+            expected
+                .to_array()
+                .iter()
+                .zip(actual.to_array().iter())
+                .any(|(e, a)| e.abs_diff(*a) > CHANNEL_TOLERANCE)
+        })
+        .count()
+}
+
+fn assert_snapshot(screen: &str, size: &ScreenSize) {
+    assert_language_snapshot(screen, Language::English, size);
+}
+
+/// Like `assert_snapshot`, the image names other languages than English
+fn assert_language_snapshot(screen: &str, language: Language, size: &ScreenSize) {
+    let name = match language {
+        Language::English => format!("{}_{}", screen, size.name),
+        _ => format!("{}_{:?}_{}", screen, language, size.name),
+    }
+    .to_lowercase();
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{}.png", name));
+
+    let actual = render(screen, language, size);
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        save_png(&actual, &path).unwrap();
+        return;
+    }
+
+    let expected = load_png(&path).unwrap();
+
+    let actual_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("{}.png", name));
+    save_png(&actual, &actual_path).unwrap();
+
+    assert_eq!(expected.size, actual.size, "{} changed its size", name);
+
+    let differing = differing_pixels(&expected, &actual);
+    assert!(
+        differing as f32 <= PIXEL_TOLERANCE * actual.pixels.len() as f32,
+        "{} differs in {} pixels, see {}",
+        name,
+        differing,
+        actual_path.display()
+    );
+}
+
+#[test]
+fn home() {
+    assert_snapshot("Home", &PHONE);
+    assert_snapshot("Home", &DESKTOP);
+}
+
+#[test]
+fn dates() {
+    assert_snapshot("Dates", &PHONE);
+    assert_snapshot("Dates", &DESKTOP);
+}
+
+#[test]
+fn settings() {
+    assert_snapshot("Settings", &PHONE);
+    assert_snapshot("Settings", &DESKTOP);
+}
+
+#[test]
+fn german() {
+    assert_language_snapshot("Home", Language::German, &PHONE);
+    assert_language_snapshot("Settings", Language::German, &PHONE);
+}
+
+#[test]
+fn software_renderer_fills_shared_edges_once() {
+    let ctx = egui::Context::default();
+
+    let image = screenshot(&ctx, vec2(64.0, 64.0), 1.0, |ctx| {
+        let painter = ctx.layer_painter(egui::LayerId::background());
+        painter.rect_filled(
+            egui::Rect::from_min_size(egui::Pos2::ZERO, vec2(64.0, 64.0)),
+            0.0,
+            egui::Color32::BLACK,
+        );
+        painter.rect_filled(
+            egui::Rect::from_min_size(egui::pos2(16.0, 16.0), vec2(32.0, 32.0)),
+            0.0,
+            egui::Color32::from_white_alpha(128),
+        );
+    });
+
+    let inside: Vec<_> = (17..47)
+        .flat_map(|y| (17..47).map(move |x| (x, y)))
+        .map(|(x, y)| image.pixels[y * 64 + x])
+        .collect();
+
+    assert!(inside.iter().all(|pixel| *pixel == inside[0]));
+    assert_ne!(inside[0], image.pixels[0]);
+}
@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+
+use android_rust_experiments::{
+    AppState, Dates, Event, Goal, GoalPeriod, Message, MessageBus, Settings, StateView,
+};
+use chrono::NaiveDate;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn state() -> AppState {
+    AppState::new(
+        vec![Event::new(date(2023, 4, 1)), Event::new(date(2023, 5, 2))],
+        vec![Goal {
+            target: 2,
+            period: GoalPeriod::Month,
+        }],
+        BTreeMap::new(),
+        Settings::default(),
+        date(2023, 6, 15),
+    )
+}
+
+#[test]
+fn messages_update_the_statistics() {
+    let mut state = state();
+
+    assert_eq!(state.statistics().consecutive_months, 2);
+    assert_eq!(state.statistics().goals[0].count, 0);
+
+    state.apply(&Message::EventAdded(Event::new(date(2023, 6, 1))));
+
+    assert_eq!(state.statistics().consecutive_months, 3);
+    assert_eq!(state.statistics().recent_events, 3);
+    assert_eq!(state.statistics().goals[0].count, 1);
+
+    state.apply(&Message::EventRemoved(Event::new(date(2023, 4, 1))));
+
+    assert_eq!(state.statistics().consecutive_months, 2);
+    assert_eq!(state.statistics().recent_events, 2);
+}
+
+#[test]
+fn settings_and_day_changes_move_the_statistic_window() {
+    let mut state = state();
+
+    state.apply(&Message::SettingsChanged(Settings {
+        statistic_months: 2,
+        ..Settings::default()
+    }));
+    assert_eq!(state.statistics().recent_events, 1);
+
+    state.apply(&Message::DayChanged(date(2023, 9, 1)));
+    assert_eq!(state.statistics().recent_events, 0);
+    assert_eq!(state.statistics().consecutive_months, 0);
+}
+
+#[test]
+fn views_send_messages_instead_of_changing_the_state() {
+    let state = state();
+    let mut bus = MessageBus::default();
+    let mut dates = Dates::default();
+
+    let ctx = egui::Context::default();
+    let mut style = (*ctx.style()).clone();
+    style.text_styles.insert(
+        egui::TextStyle::Name("DateInputButton".into()),
+        egui::FontId::proportional(28.0),
+    );
+    ctx.set_style(style);
+
+    let mut run = |events: Vec<egui::Event>| {
+        let raw_input = egui::RawInput {
+            screen_rect: Some(egui::Rect::from_min_size(
+                egui::Pos2::ZERO,
+                egui::vec2(400.0, 800.0),
+            )),
+            events,
+            ..Default::default()
+        };
+
+        let _ = ctx.run(raw_input, |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| dates.ui(ui, &state, &mut bus));
+        });
+    };
+
+    run(Vec::new());
+
+    let tab = egui::Event::Key {
+        key: egui::Key::Tab,
+        pressed: true,
+        repeat: false,
+        modifiers: egui::Modifiers::NONE,
+    };
+    let enter = egui::Event::Key {
+        key: egui::Key::Enter,
+        pressed: true,
+        repeat: false,
+        modifiers: egui::Modifiers::NONE,
+    };
+
+    // Two delete buttons, the three wheels and the calendar button come before "+"
+    for _ in 0..7 {
+        run(vec![tab.clone()]);
+    }
+    run(vec![enter]);
+
+    assert_eq!(
+        bus.take(),
+        [Message::EventAdded(Event::new(date(2023, 6, 15)))]
+    );
+    assert_eq!(state.events().len(), 2);
+}
+
+#[test]
+fn trackers_come_from_the_events_the_rules_and_the_settings() {
+    let mut rules = BTreeMap::new();
+    rules.insert("running".to_owned(), Default::default());
+    let settings = Settings {
+        default_tracker: "reading".to_owned(),
+        ..Settings::default()
+    };
+
+    let state = AppState::new(
+        vec![Event::of_tracker(date(2023, 6, 1), "gym")],
+        Vec::new(),
+        rules,
+        settings,
+        date(2023, 6, 15),
+    );
+
+    assert_eq!(
+        state.trackers().into_iter().collect::<Vec<_>>(),
+        ["default", "gym", "reading", "running"]
+    );
+}
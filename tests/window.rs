@@ -0,0 +1,91 @@
+use android_rust_experiments::{MemoryStorage, Storage, WindowGeometry, WindowOptions, PHONE_SIZE};
+
+fn saved() -> WindowGeometry {
+    WindowGeometry {
+        position: Some([-1200, 40]),
+        size: [900, 700],
+        maximized: true,
+    }
+}
+
+#[test]
+fn geometry_is_saved_and_restored() {
+    let storage = MemoryStorage::default();
+
+    assert_eq!(WindowGeometry::load(&storage), None);
+
+    saved().save(&storage).unwrap();
+
+    assert_eq!(WindowGeometry::load(&storage), Some(saved()));
+}
+
+#[test]
+fn invalid_geometry_lines_are_skipped() {
+    let storage = MemoryStorage::default();
+    storage
+        .write(
+            "window.dat",
+            "position left top\nsize 900 700\nmaximized\nzoom 2\n\n",
+        )
+        .unwrap();
+
+    assert_eq!(
+        WindowGeometry::load(&storage),
+        Some(WindowGeometry {
+            position: None,
+            size: [900, 700],
+            maximized: false,
+        })
+    );
+
+    // Without a size the default geometry is used
+    storage.write("window.dat", "size 900\n").unwrap();
+
+    assert_eq!(WindowGeometry::load(&storage), None);
+}
+
+#[test]
+fn windows_open_where_they_were() {
+    let options = WindowOptions::default();
+
+    assert_eq!(options.initial_geometry(Some(saved())), saved());
+    assert_eq!(
+        options.initial_geometry(None),
+        WindowGeometry {
+            position: None,
+            size: options.size,
+            maximized: false,
+        }
+    );
+}
+
+#[test]
+fn saved_sizes_respect_the_minimum_size() {
+    let options = WindowOptions::default();
+    let tiny = WindowGeometry {
+        size: [10, 2000],
+        ..saved()
+    };
+
+    assert_eq!(
+        options.initial_geometry(Some(tiny)).size,
+        [options.min_size[0], 2000]
+    );
+}
+
+#[test]
+fn the_phone_preview_has_a_phone_size() {
+    let options = WindowOptions {
+        phone_preview: true,
+        ..WindowOptions::default()
+    };
+
+    assert_eq!(
+        options.initial_geometry(Some(saved())),
+        WindowGeometry {
+            position: Some([-1200, 40]),
+            size: PHONE_SIZE,
+            maximized: false,
+        }
+    );
+}